@@ -7,6 +7,7 @@ pub mod paras_launchpad {
     use openbrush::{
         contracts::{
             ownable::*,
+            pausable::*,
             psp34::extensions::{enumerable::*, metadata::*},
         },
         modifiers,
@@ -15,8 +16,17 @@ pub mod paras_launchpad {
 
     use ink::prelude::vec::Vec;
 
+    #[cfg(feature = "refundable")]
+    use psp34_extension_pkg::traits::refundable::*;
     use psp34_extension_pkg::{
-        impls::launchpad::{types::Percentage, *},
+        impls::launchpad::{
+            types::{
+                DutchAuction, IdAssignment, MintingStatus, Percentage, Phase, PhaseId,
+                PhaseSchedule, RedemptionTierId, DEFAULT_RAFFLE_DRAW_TIMEOUT,
+            },
+            *,
+        },
+        impls::psp34_traits::*,
         traits::launchpad::*,
         traits::psp34_traits::*,
     };
@@ -30,15 +40,33 @@ pub mod paras_launchpad {
         #[storage_field]
         ownable: ownable::Data,
         #[storage_field]
+        pausable: pausable::Data,
+        #[storage_field]
         metadata: metadata::Data,
         #[storage_field]
         launchpad: types::Data,
     }
 
-    impl PSP34 for ParasLaunchpadContract {}
+    impl PSP34 for ParasLaunchpadContract {
+        #[modifiers(when_not_paused)]
+        fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34Error> {
+            self._transfer_token(to, id, data)
+        }
+
+        #[modifiers(when_not_paused)]
+        fn approve(
+            &mut self,
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            self._approve_for(operator, id, approved)
+        }
+    }
     impl PSP34Enumerable for ParasLaunchpadContract {}
     impl PSP34Metadata for ParasLaunchpadContract {}
     impl Ownable for ParasLaunchpadContract {}
+    impl Pausable for ParasLaunchpadContract {}
 
     /// Event emitted when a token transfer occurs.
     #[ink(event)]
@@ -63,13 +91,197 @@ pub mod paras_launchpad {
         approved: bool,
     }
 
+    /// Event emitted when the contract is paused.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the contract is unpaused.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when a phase of the sale schedule is added, moved or removed.
+    #[ink(event)]
+    pub struct ScheduleChanged {
+        #[ink(topic)]
+        phase_id: PhaseId,
+        old_schedule: Option<PhaseSchedule>,
+        new_schedule: Option<PhaseSchedule>,
+    }
+
+    /// Event emitted when the owner forces or clears the minting status.
+    #[ink(event)]
+    pub struct MintingStatusForced {
+        minting_status: Option<MintingStatus>,
+    }
+
+    /// Event emitted when the owner sets, rotates or removes the voucher signer.
+    #[ink(event)]
+    pub struct VoucherSignerChanged {
+        voucher_signer: Option<[u8; 33]>,
+    }
+
+    /// Event emitted when an account is added to, updated on or removed from an allowlist.
+    #[ink(event)]
+    pub struct AllowlistUpdated {
+        #[ink(topic)]
+        phase_id: PhaseId,
+        #[ink(topic)]
+        account: AccountId,
+        old_amount: Option<u64>,
+        new_amount: Option<u64>,
+    }
+
+    /// Event emitted when the collection metadata is revealed.
+    #[ink(event)]
+    pub struct Revealed {
+        base_uri: String,
+    }
+
+    /// Event emitted when tokens are burned to redeem a token of a redemption tier.
+    #[ink(event)]
+    pub struct Redeemed {
+        #[ink(topic)]
+        account: AccountId,
+        tier: RedemptionTierId,
+        burned_ids: Vec<Id>,
+        id: Id,
+    }
+
+    /// Event emitted when a token is handed over to the refund address for a refund.
+    #[cfg(feature = "refundable")]
+    #[ink(event)]
+    pub struct Refund {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        id: Option<Id>,
+        price: Balance,
+        refunded: Balance,
+    }
+
     impl ParasLaunchpadContract {
+        #[cfg(not(feature = "refundable"))]
+        #[ink(constructor)]
+        pub fn new(
+            name: String,
+            symbol: String,
+            base_uri: String,
+            placeholder_uri: Option<String>,
+            provenance_hash: Option<String>,
+            max_supply: u64,
+            id_assignment: IdAssignment,
+            reserved_supply: u64,
+            prepresale_price_per_mint: Balance,
+            presale_price_per_mint: Balance,
+            price_per_mint: Balance,
+            prepresale_start_at: u64,
+            presale_start_at: u64,
+            public_sale_start_at: u64,
+            public_sale_end_at: Option<u64>,
+            launchpad_fee: Percentage,
+            project_treasury: AccountId,
+            launchpad_treasury: AccountId,
+        ) -> Self {
+            Self::init(
+                name,
+                symbol,
+                base_uri,
+                placeholder_uri,
+                provenance_hash,
+                max_supply,
+                id_assignment,
+                reserved_supply,
+                prepresale_price_per_mint,
+                presale_price_per_mint,
+                price_per_mint,
+                prepresale_start_at,
+                presale_start_at,
+                public_sale_start_at,
+                public_sale_end_at,
+                launchpad_fee,
+                project_treasury,
+                launchpad_treasury,
+            )
+        }
+
+        /// Takes the refund policy where the released refundable contract does, after the end of
+        /// the public sale
+        #[cfg(feature = "refundable")]
         #[ink(constructor)]
         pub fn new(
             name: String,
             symbol: String,
             base_uri: String,
+            placeholder_uri: Option<String>,
+            provenance_hash: Option<String>,
+            max_supply: u64,
+            id_assignment: IdAssignment,
+            reserved_supply: u64,
+            prepresale_price_per_mint: Balance,
+            presale_price_per_mint: Balance,
+            price_per_mint: Balance,
+            prepresale_start_at: u64,
+            presale_start_at: u64,
+            public_sale_start_at: u64,
+            public_sale_end_at: Option<u64>,
+            refund_periods: Vec<u64>,
+            refund_shares: Vec<Percentage>,
+            refund_address: AccountId,
+            launchpad_fee: Percentage,
+            project_treasury: AccountId,
+            launchpad_treasury: AccountId,
+        ) -> Self {
+            let mut instance = Self::init(
+                name,
+                symbol,
+                base_uri,
+                placeholder_uri,
+                provenance_hash,
+                max_supply,
+                id_assignment,
+                reserved_supply,
+                prepresale_price_per_mint,
+                presale_price_per_mint,
+                price_per_mint,
+                prepresale_start_at,
+                presale_start_at,
+                public_sale_start_at,
+                public_sale_end_at,
+                launchpad_fee,
+                project_treasury,
+                launchpad_treasury,
+            );
+
+            assert!(refundable::Internal::check_refund_policy(
+                &instance,
+                &refund_periods,
+                &refund_shares
+            )
+            .is_ok());
+            instance.launchpad.refund_periods = refund_periods;
+            instance.launchpad.refund_shares = refund_shares;
+            instance.launchpad.refund_address = Some(refund_address);
+
+            instance
+        }
+
+        fn init(
+            name: String,
+            symbol: String,
+            base_uri: String,
+            placeholder_uri: Option<String>,
+            provenance_hash: Option<String>,
             max_supply: u64,
+            id_assignment: IdAssignment,
+            reserved_supply: u64,
             prepresale_price_per_mint: Balance,
             presale_price_per_mint: Balance,
             price_per_mint: Balance,
@@ -87,28 +299,74 @@ pub mod paras_launchpad {
             let collection_id = instance.collection_id();
             instance._set_attribute(collection_id.clone(), String::from("name"), name);
             instance._set_attribute(collection_id.clone(), String::from("symbol"), symbol);
-            instance._set_attribute(collection_id, String::from("baseUri"), base_uri);
+            // the baseUri is readable through get_attribute, a delayed reveal sets it on reveal
+            let base_uri_is_empty = base_uri.is_empty();
+            if placeholder_uri.is_none() {
+                instance._set_attribute(collection_id, String::from("baseUri"), base_uri);
+            }
 
             instance.launchpad.max_supply = max_supply;
 
-            // public
-            instance.launchpad.price_per_mint = price_per_mint;
-            instance.launchpad.prepresale_price_per_mint = prepresale_price_per_mint;
-            instance.launchpad.presale_price_per_mint = presale_price_per_mint;
+            // reveal
+            instance.launchpad.revealed = placeholder_uri.is_none();
+            instance.launchpad.placeholder_uri = placeholder_uri.unwrap_or_default();
+            instance.launchpad.provenance_hash = provenance_hash;
 
             instance.launchpad.max_amount = 10;
-            instance.launchpad.token_set = (1..max_supply as u16 + 1)
-                .map(u16::from)
-                .collect::<Vec<u16>>();
-            instance.launchpad.pseudo_random_salt = 0;
+            instance.launchpad.token_set_length = max_supply;
+            instance.launchpad.id_assignment = id_assignment;
+            instance.launchpad.reserved_supply = reserved_supply;
+            instance.launchpad.raffle_draw_timeout = DEFAULT_RAFFLE_DRAW_TIMEOUT;
             instance.launchpad.project_treasury = Some(project_treasury);
-            instance.launchpad.prepresale_start_at = prepresale_start_at;
-            instance.launchpad.presale_start_at = presale_start_at;
-            instance.launchpad.public_sale_start_at = public_sale_start_at;
-            instance.launchpad.public_sale_end_at = public_sale_end_at;
+
+            // schedule, replaceable with `set_phases` before the sale starts
+            instance.launchpad.phases = Vec::from([
+                Phase {
+                    name: String::from("prepresale"),
+                    start_at: prepresale_start_at,
+                    end_at: None,
+                    price: prepresale_price_per_mint,
+                    dutch_auction: None,
+                    raffle: false,
+                    allowlist_only: true,
+                    max_per_account: None,
+                    max_supply: None,
+                },
+                Phase {
+                    name: String::from("presale"),
+                    start_at: presale_start_at,
+                    end_at: None,
+                    price: presale_price_per_mint,
+                    dutch_auction: None,
+                    raffle: false,
+                    allowlist_only: true,
+                    max_per_account: None,
+                    max_supply: None,
+                },
+                Phase {
+                    name: String::from("public"),
+                    start_at: public_sale_start_at,
+                    end_at: public_sale_end_at,
+                    price: price_per_mint,
+                    dutch_auction: None,
+                    raffle: false,
+                    allowlist_only: false,
+                    max_per_account: None,
+                    max_supply: None,
+                },
+            ]);
 
             // validation
             assert!(launchpad_fee < 100);
+            assert!(reserved_supply <= max_supply);
+            // a delayed reveal sets the baseUri on reveal, it can not be given as well
+            assert!(instance.launchpad.revealed || base_uri_is_empty);
+            assert!(core::str::from_utf8(&instance.launchpad.placeholder_uri).is_ok());
+            assert!(instance
+                .launchpad
+                .provenance_hash
+                .as_ref()
+                .map_or(true, |hash| core::str::from_utf8(hash).is_ok()));
 
             instance.launchpad.total_sales = 0;
             instance.launchpad.withdrawn_sales_launchpad = 0;
@@ -156,8 +414,107 @@ pub mod paras_launchpad {
         }
     }
 
+    impl pausable::Internal for ParasLaunchpadContract {
+        fn _emit_paused_event(&self, account: AccountId) {
+            self.env().emit_event(Paused { account });
+        }
+
+        fn _emit_unpaused_event(&self, account: AccountId) {
+            self.env().emit_event(Unpaused { account });
+        }
+    }
+
+    impl psp34_traits::Internal for ParasLaunchpadContract {
+        fn _emit_revealed_event(&self, base_uri: String) {
+            self.env().emit_event(Revealed { base_uri });
+        }
+    }
+
+    impl launchpad::Internal for ParasLaunchpadContract {
+        // partner collections cannot be called off-chain, tests hand out their tokens instead
+        #[cfg(test)]
+        fn get_gated_token_owner(&self, collection: AccountId, id: Id) -> Option<AccountId> {
+            tests::partner_token_owner(collection, id)
+        }
+
+        fn _emit_minting_status_forced_event(&self, minting_status: Option<MintingStatus>) {
+            self.env()
+                .emit_event(MintingStatusForced { minting_status });
+        }
+
+        fn _emit_voucher_signer_changed_event(&self, voucher_signer: Option<[u8; 33]>) {
+            self.env()
+                .emit_event(VoucherSignerChanged { voucher_signer });
+        }
+
+        fn _emit_allowlist_updated_event(
+            &self,
+            phase_id: PhaseId,
+            account: AccountId,
+            old_amount: Option<u64>,
+            new_amount: Option<u64>,
+        ) {
+            self.env().emit_event(AllowlistUpdated {
+                phase_id,
+                account,
+                old_amount,
+                new_amount,
+            });
+        }
+
+        fn _emit_schedule_changed_event(
+            &self,
+            phase_id: PhaseId,
+            old_schedule: Option<PhaseSchedule>,
+            new_schedule: Option<PhaseSchedule>,
+        ) {
+            self.env().emit_event(ScheduleChanged {
+                phase_id,
+                old_schedule,
+                new_schedule,
+            });
+        }
+
+        fn _emit_redeemed_event(
+            &self,
+            account: AccountId,
+            tier: RedemptionTierId,
+            burned_ids: Vec<Id>,
+            id: Id,
+        ) {
+            self.env().emit_event(Redeemed {
+                account,
+                tier,
+                burned_ids,
+                id,
+            });
+        }
+    }
+
+    #[cfg(feature = "refundable")]
+    impl refundable::Internal for ParasLaunchpadContract {
+        fn _emit_refund_event(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            id: Option<Id>,
+            price: Balance,
+            refunded: Balance,
+        ) {
+            self.env().emit_event(Refund {
+                from,
+                to,
+                id,
+                price,
+                refunded,
+            });
+        }
+    }
+
     impl Launchpad for ParasLaunchpadContract {}
     impl Psp34Traits for ParasLaunchpadContract {}
+    #[cfg(feature = "refundable")]
+    impl Refundable for ParasLaunchpadContract {}
 
     // ------------------- T E S T -----------------------------------------------------
     #[cfg(test)]
@@ -167,8 +524,15 @@ pub mod paras_launchpad {
         use ink::prelude::string::String as PreludeString;
         use psp34_extension_pkg::impls::launchpad::{
             launchpad::Internal,
-            types::{MintingStatus, Shiden34Error},
+            merkle::{allowlist_leaf, hash_pair},
+            randomness::{
+                keccak256, reduce_uniform, shuffle_index, DeterministicRandomness, RandomnessSource,
+            },
+            types::{MintVoucher, Shiden34Error, TokenGate, MAX_GATED_TOKENS},
+            voucher::voucher_message_hash,
         };
+        use secp256k1::{ecdsa::RecoverableSignature, Message, PublicKey, Secp256k1, SecretKey};
+        use std::cell::RefCell;
         const PRICE: Balance = 100_000_000_000_000_000;
         const PREPRESALE_PRICE: Balance = 10_000_000_000_000_000;
         const PRESALE_PRICE: Balance = 20_000_000_000_000_000;
@@ -176,6 +540,7 @@ pub mod paras_launchpad {
         const MAX_SUPPLY: u64 = 10000;
 
         const PUBLIC_SALE_END_AT: u64 = 1682899200000;
+        const METADATA_SECRET: &str = "metadata secret";
         const ONE_MONTH_IN_MILLIS: u64 = 2592000000;
 
         #[ink::test]
@@ -199,12 +564,119 @@ pub mod paras_launchpad {
         }
 
         fn init() -> ParasLaunchpadContract {
+            init_with_max_supply(MAX_SUPPLY)
+        }
+
+        fn init_with_max_supply(max_supply: u64) -> ParasLaunchpadContract {
+            init_with(max_supply, IdAssignment::Random, 0)
+        }
+
+        fn init_with(
+            max_supply: u64,
+            id_assignment: IdAssignment,
+            reserved_supply: u64,
+        ) -> ParasLaunchpadContract {
+            let mut sh34 = init_unrevealed(max_supply, id_assignment, reserved_supply);
+            // taken by the first mint for the metadata assignment
+            assert!(sh34
+                .commit_randomness(keccak256(METADATA_SECRET.as_bytes()))
+                .is_ok());
+            sh34
+        }
+
+        fn reveal_metadata(sh34: &mut ParasLaunchpadContract) {
+            set_sender(default_accounts().alice);
+            assert!(sh34.set_minting_status(Some(MintingStatus::End)).is_ok());
+            assert!(sh34
+                .reveal_randomness(METADATA_SECRET.as_bytes().to_vec())
+                .is_ok());
+        }
+
+        /// Deploy with the arguments both constructors take, the refundable one without refunds
+        #[allow(clippy::too_many_arguments, clippy::let_and_return)]
+        fn new_contract(
+            name: String,
+            symbol: String,
+            base_uri: String,
+            placeholder_uri: Option<String>,
+            provenance_hash: Option<String>,
+            max_supply: u64,
+            id_assignment: IdAssignment,
+            reserved_supply: u64,
+            prepresale_price_per_mint: Balance,
+            presale_price_per_mint: Balance,
+            price_per_mint: Balance,
+            prepresale_start_at: u64,
+            presale_start_at: u64,
+            public_sale_start_at: u64,
+            public_sale_end_at: Option<u64>,
+            launchpad_fee: Percentage,
+            project_treasury: AccountId,
+            launchpad_treasury: AccountId,
+        ) -> ParasLaunchpadContract {
+            #[cfg(not(feature = "refundable"))]
+            let sh34 = ParasLaunchpadContract::new(
+                name,
+                symbol,
+                base_uri,
+                placeholder_uri,
+                provenance_hash,
+                max_supply,
+                id_assignment,
+                reserved_supply,
+                prepresale_price_per_mint,
+                presale_price_per_mint,
+                price_per_mint,
+                prepresale_start_at,
+                presale_start_at,
+                public_sale_start_at,
+                public_sale_end_at,
+                launchpad_fee,
+                project_treasury,
+                launchpad_treasury,
+            );
+            #[cfg(feature = "refundable")]
+            let sh34 = ParasLaunchpadContract::new(
+                name,
+                symbol,
+                base_uri,
+                placeholder_uri,
+                provenance_hash,
+                max_supply,
+                id_assignment,
+                reserved_supply,
+                prepresale_price_per_mint,
+                presale_price_per_mint,
+                price_per_mint,
+                prepresale_start_at,
+                presale_start_at,
+                public_sale_start_at,
+                public_sale_end_at,
+                Vec::new(),
+                Vec::new(),
+                default_accounts().frank,
+                launchpad_fee,
+                project_treasury,
+                launchpad_treasury,
+            );
+            sh34
+        }
+
+        fn init_unrevealed(
+            max_supply: u64,
+            id_assignment: IdAssignment,
+            reserved_supply: u64,
+        ) -> ParasLaunchpadContract {
             let accounts = default_accounts();
-            ParasLaunchpadContract::new(
+            new_contract(
                 String::from("Shiden34"), // name: String,
                 String::from("SH34"),     // symbol: String,
                 String::from(BASE_URI),   // base_uri: String,
-                MAX_SUPPLY,               // max_supply: u64,
+                None,                     // placeholder_uri: Option<String>,
+                None,                     // provenance_hash: Option<String>,
+                max_supply,               // max_supply: u64,
+                id_assignment,            // id_assignment: IdAssignment,
+                reserved_supply,          // reserved_supply: u64,
                 PREPRESALE_PRICE,         // prepresale_price_per_mint: Balance,
                 PRESALE_PRICE,            // presale_price_per_mint: Balance
                 PRICE,                    // price_per_mint: Balance,
@@ -225,7 +697,9 @@ pub mod paras_launchpad {
             assert_eq!(sh34.owner(), accounts.alice);
 
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(3)).is_ok());
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
 
             set_sender(accounts.bob);
 
@@ -241,7 +715,7 @@ pub mod paras_launchpad {
             );
             assert_eq!(sh34.balance_of(accounts.bob), 1);
 
-            assert_eq!(1, ink::env::test::recorded_events().count());
+            assert_eq!(2, ink::env::test::recorded_events().count());
         }
 
         #[ink::test]
@@ -249,7 +723,37 @@ pub mod paras_launchpad {
             let mut sh34 = init();
             let accounts = default_accounts();
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(1)).is_ok()); // prepresale
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(0)))
+                .is_ok()); // prepresale
+        }
+
+        #[ink::test]
+        fn forced_minting_status_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            assert_eq!(sh34.get_forced_minting_status(), None);
+            assert_eq!(
+                sh34.set_minting_status(Some(MintingStatus::Phase(3))),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidPhase.as_str()))
+            );
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(1)))
+                .is_ok());
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Phase(1));
+            assert_eq!(
+                sh34.get_forced_minting_status(),
+                Some(MintingStatus::Phase(1))
+            );
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1);
+            assert!(sh34.set_minting_status(None).is_ok());
+            assert_eq!(sh34.get_forced_minting_status(), None);
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Phase(2));
+
+            assert_eq!(2, ink::env::test::recorded_events().count());
         }
 
         #[ink::test]
@@ -257,7 +761,9 @@ pub mod paras_launchpad {
             let mut sh34 = init();
             let accounts = default_accounts();
             set_sender(accounts.bob);
-            assert!(sh34.set_minting_status(Some(1)).is_err()); // prepresale
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(0)))
+                .is_err()); // prepresale
         }
 
         #[ink::test]
@@ -296,7 +802,9 @@ pub mod paras_launchpad {
             assert_eq!(sh34.owner(), accounts.alice);
 
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(1)).is_ok()); // prepresale
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(0)))
+                .is_ok()); // prepresale
             assert!(sh34.add_account_to_prepresale(accounts.bob, 1).is_ok());
 
             set_sender(accounts.bob);
@@ -318,7 +826,7 @@ pub mod paras_launchpad {
             );
             assert_eq!(sh34.balance_of(accounts.bob), 1);
 
-            assert_eq!(1, ink::env::test::recorded_events().count());
+            assert_eq!(3, ink::env::test::recorded_events().count());
         }
 
         #[ink::test]
@@ -328,7 +836,9 @@ pub mod paras_launchpad {
             assert_eq!(sh34.owner(), accounts.alice);
 
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(2)).is_ok()); // preesale
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(1)))
+                .is_ok()); // presale
             assert!(sh34.add_account_to_presale(accounts.bob, 1).is_ok());
 
             set_sender(accounts.bob);
@@ -350,107 +860,2216 @@ pub mod paras_launchpad {
             );
             assert_eq!(sh34.balance_of(accounts.bob), 1);
 
-            assert_eq!(1, ink::env::test::recorded_events().count());
+            assert_eq!(3, ink::env::test::recorded_events().count());
         }
 
         #[ink::test]
-        fn withdraw_launchpad_works() {
+        fn public_mint_limit_per_account_works() {
             let mut sh34 = init();
             let accounts = default_accounts();
-            assert_eq!(sh34.owner(), accounts.alice);
-
-            set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(2)).is_ok()); // presale
-            assert!(sh34.add_account_to_presale(accounts.bob, 1).is_ok());
 
             set_sender(accounts.bob);
-
-            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.bob, PRESALE_PRICE);
-            assert!(pay_with_call!(sh34.mint_next(), PRESALE_PRICE).is_ok());
+            assert!(sh34.set_max_public_mint_per_account(Some(2)).is_err());
 
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(4)).is_ok());
-
-            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.django, 0);
-
-            // after mint  withdraw
-            test::set_block_timestamp::<ink::env::DefaultEnvironment>(PUBLIC_SALE_END_AT + 1);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+            assert!(sh34.set_max_public_mint_per_account(Some(2)).is_ok());
+            assert_eq!(sh34.get_max_public_mint_per_account(), Some(2));
 
-            set_sender(accounts.django);
-            assert!(sh34.withdraw_launchpad().is_ok());
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 2);
+            assert!(sh34.mint(accounts.bob, 2).is_ok());
+            assert_eq!(sh34.get_account_public_minted_amount(accounts.bob), 2);
 
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
             assert_eq!(
-                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.django)
-                    .ok()
-                    .unwrap(),
-                (PRESALE_PRICE * 10) / 100
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::AccountMintLimitExceeded.as_str()
+                ))
             );
-
-            assert_eq!(1, ink::env::test::recorded_events().count());
+            assert_eq!(sh34.total_supply(), 2);
         }
 
         #[ink::test]
-        fn withdraw_project_works() {
+        fn phase_schedule_works() {
             let mut sh34 = init();
             let accounts = default_accounts();
-            assert_eq!(sh34.owner(), accounts.alice);
 
-            set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(2)).is_ok()); // presale
-            assert!(sh34.add_account_to_presale(accounts.bob, 1).is_ok());
+            let mut og = phase("og", 100, Some(200), PREPRESALE_PRICE, true);
+            og.max_per_account = Some(1);
+            let mut waitlist = phase("waitlist", 300, None, PRESALE_PRICE, false);
+            waitlist.max_supply = Some(2);
+            let public = phase("public", 400, Some(500), PRICE, false);
 
             set_sender(accounts.bob);
-
-            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.bob, PRESALE_PRICE);
-            assert!(pay_with_call!(sh34.mint_next(), PRESALE_PRICE).is_ok());
+            assert!(sh34
+                .set_phases(Vec::from([og.clone(), waitlist.clone(), public.clone()]))
+                .is_err());
 
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(4)).is_ok());
+            assert!(sh34.set_phases(Vec::from([og, waitlist, public])).is_ok());
+            assert!(sh34.add_account_to_allowlist(0, accounts.bob, 2).is_ok());
+            assert!(sh34.add_account_to_allowlist(3, accounts.bob, 2).is_err());
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Closed);
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(150);
+            assert_eq!(sh34.get_current_phase(), Some(0));
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Phase(0));
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE);
+            assert!(sh34.mint_next().is_ok());
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::AccountMintLimitExceeded.as_str()
+                ))
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
 
-            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie, 0);
+            // the sale is closed between an early phase end and the next phase
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(250);
+            assert_eq!(sh34.get_current_phase(), None);
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Closed);
 
-            // after mint withdraw
-            test::set_block_timestamp::<ink::env::DefaultEnvironment>(PUBLIC_SALE_END_AT + 1);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(350);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE * 2);
+            assert!(sh34.mint(accounts.charlie, 2).is_ok());
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE);
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(Shiden34Error::PhaseIsFull.as_str()))
+            );
+            assert_eq!(sh34.get_phase_minted_amount(1), 2);
+            assert_eq!(sh34.get_account_phase_minted_amount(1, accounts.charlie), 2);
 
-            set_sender(accounts.charlie);
-            assert!(sh34.withdraw_project().is_ok());
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(450);
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Phase(2));
+            assert_eq!(sh34.price(), PRICE);
 
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(501);
+            assert_eq!(sh34.get_minting_status(), MintingStatus::End);
+            assert_eq!(sh34.total_supply(), 3);
+
+            set_sender(accounts.alice);
             assert_eq!(
-                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie)
-                    .ok()
-                    .unwrap(),
-                (PRESALE_PRICE * 90) / 100
+                sh34.set_phases(Vec::new()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::PhaseAlreadyRunning.as_str()
+                ))
             );
-
-            assert_eq!(1, ink::env::test::recorded_events().count());
         }
 
         #[ink::test]
-        fn mint_multiple_works() {
+        fn set_phases_keeps_started_phases() {
             let mut sh34 = init();
             let accounts = default_accounts();
+            let og = phase("og", 100, Some(200), PREPRESALE_PRICE, false);
+            let waitlist = phase("waitlist", 300, None, PRESALE_PRICE, false);
+            let public = phase("public", 400, Some(500), PRICE, false);
 
             set_sender(accounts.alice);
-            let num_of_mints: u64 = 5;
-            // Set max limit to 'num_of_mints', fails to mint 'num_of_mints + 1'. Caller is contract owner
-            assert!(sh34.set_max_mint_amount(num_of_mints).is_ok());
-            assert!(sh34.set_minting_status(Some(3)).is_ok());
-
+            assert!(sh34
+                .set_phases(Vec::from([og.clone(), waitlist.clone(), public.clone()]))
+                .is_ok());
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(150);
+
+            // the terms of the running phase are kept
+            let mut cheaper_og = og.clone();
+            cheaper_og.price = 1;
             assert_eq!(
-                sh34.mint(accounts.bob, num_of_mints + 1),
+                sh34.set_phases(Vec::from([cheaper_og, waitlist.clone(), public.clone()])),
                 Err(PSP34Error::Custom(
-                    Shiden34Error::TooManyTokensToMint.as_str()
+                    Shiden34Error::PhaseAlreadyRunning.as_str()
                 ))
             );
-
+            let mut early_waitlist = waitlist.clone();
+            early_waitlist.start_at = 150;
+            assert_eq!(
+                sh34.set_phases(Vec::from([og.clone(), early_waitlist, public.clone()])),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidSchedule.as_str()))
+            );
+
+            // the running phase can be extended and the pending ones moved
+            let events_before = ink::env::test::recorded_events().count();
+            let mut longer_og = og.clone();
+            longer_og.end_at = Some(250);
+            let mut later_waitlist = waitlist.clone();
+            later_waitlist.start_at = 350;
+            let mut longer_public = public;
+            longer_public.end_at = Some(600);
+            assert!(sh34
+                .set_phases(Vec::from([
+                    longer_og.clone(),
+                    later_waitlist.clone(),
+                    longer_public
+                ]))
+                .is_ok());
+            assert!(sh34
+                .set_phases(Vec::from([longer_og.clone(), later_waitlist.clone()]))
+                .is_ok());
+            assert_eq!(sh34.get_public_sale_end_at(), None);
+
+            let schedules = ink::env::test::recorded_events()
+                .skip(events_before)
+                .map(|event| {
+                    match <Event as scale::Decode>::decode(&mut &event.data[..]).unwrap() {
+                        Event::ScheduleChanged(ScheduleChanged {
+                            phase_id,
+                            old_schedule,
+                            new_schedule,
+                        }) => (phase_id, old_schedule, new_schedule),
+                        _ => panic!("unexpected event"),
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                schedules,
+                Vec::from([
+                    (0, Some((100, Some(200))), Some((100, Some(250)))),
+                    (1, Some((300, None)), Some((350, None))),
+                    (2, Some((400, Some(500))), Some((400, Some(600)))),
+                    (2, Some((400, Some(600))), None),
+                ])
+            );
+
+            // an ended phase is kept as it is
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(260);
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Closed);
+            assert_eq!(
+                sh34.set_phases(Vec::from([og, later_waitlist])),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::PhaseAlreadyRunning.as_str()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn phase_allocation_rolls_over() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert!(sh34.set_phase_allocation(0, Some(2)).is_err());
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.set_phase_allocation(3, Some(2)),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidPhase.as_str()))
+            );
+            assert!(sh34.set_phase_allocation(0, Some(2)).is_ok());
+            assert!(sh34.set_phase_allocation(1, Some(3)).is_ok());
+            assert!(sh34.add_account_to_prepresale(accounts.bob, 5).is_ok());
+            assert!(sh34.add_account_to_presale(accounts.bob, 5).is_ok());
+
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(0)))
+                .is_ok());
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE);
+            assert!(sh34.mint_next().is_ok());
+            assert_eq!(sh34.get_phase_remaining_allocation(0), Some(1));
+            // the unsold prepresale token rolls over to the presale
+            assert_eq!(sh34.get_phase_remaining_allocation(1), Some(4));
+            assert_eq!(sh34.get_phase_remaining_allocation(2), None);
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(1)))
+                .is_ok());
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE * 5);
+            assert_eq!(
+                sh34.mint(accounts.bob, 5),
+                Err(PSP34Error::Custom(Shiden34Error::PhaseIsFull.as_str()))
+            );
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE * 4);
+            assert!(sh34.mint(accounts.bob, 4).is_ok());
+            assert_eq!(sh34.get_phase_remaining_allocation(1), Some(0));
+            assert_eq!(sh34.total_supply(), 5);
+        }
+
+        #[ink::test]
+        fn dutch_auction_rebate_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            let dutch_auction = DutchAuction {
+                floor_price: 40,
+                price_step: 10,
+                step_interval: 50,
+                rebate: true,
+            };
+
+            set_sender(accounts.alice);
+            let mut auction = phase("auction", 100, Some(1000), 100, false);
+            auction.dutch_auction = Some(DutchAuction {
+                floor_price: 200,
+                ..dutch_auction
+            });
+            assert_eq!(
+                sh34.set_phases(Vec::from([auction.clone()])),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidSchedule.as_str()))
+            );
+            auction.dutch_auction = Some(dutch_auction);
+            assert!(sh34.set_phases(Vec::from([auction])).is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(101);
+            assert_eq!(sh34.get_phase_price(0), Ok(100));
+            set_balance(accounts.bob, 200);
+            set_sender(accounts.bob);
+            assert!(pay_with_call!(sh34.mint(accounts.bob, 2), 200).is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(260);
+            assert_eq!(sh34.price(), 70);
+            set_balance(accounts.charlie, 70);
+            set_sender(accounts.charlie);
+            assert!(pay_with_call!(sh34.mint_next(), 70).is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(900);
+            assert_eq!(sh34.price(), 40);
+            set_sender(accounts.bob);
+            assert_eq!(
+                sh34.claim_rebate(0),
+                Err(PSP34Error::Custom(Shiden34Error::AuctionNotEnded.as_str()))
+            );
+
+            // the auction cleared at the price of the last mint
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001);
+            assert_eq!(sh34.get_rebate_amount(0, accounts.bob), 60);
+            assert_eq!(sh34.get_rebate_amount(0, accounts.charlie), 0);
+            assert_eq!(sh34.get_available_to_withdraw_project(), 189);
+
+            assert!(sh34.claim_rebate(0).is_ok());
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .ok()
+                    .unwrap(),
+                60
+            );
+            assert_eq!(
+                sh34.claim_rebate(0),
+                Err(PSP34Error::Custom(Shiden34Error::NoRebate.as_str()))
+            );
+            assert_eq!(sh34.get_available_to_withdraw_project(), 189);
+            assert_eq!(sh34.get_available_to_withdraw_launchpad(), 21);
+        }
+
+        #[ink::test]
+        fn schedule_setters_work() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert!(sh34.set_presale_start_at(200).is_err());
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.set_prepresale_start_at(100),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidSchedule.as_str()))
+            );
+            assert!(sh34.set_public_sale_start_at(300).is_ok());
+            assert!(sh34.set_presale_start_at(200).is_ok());
+            assert!(sh34.set_prepresale_start_at(100).is_ok());
+            assert_eq!(sh34.get_prepresale_start_at(), 100);
+            assert_eq!(sh34.get_presale_start_at(), 200);
+            assert_eq!(sh34.get_public_sale_start_at(), 300);
+            assert_eq!(
+                sh34.set_public_sale_end_at(Some(300)),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidSchedule.as_str()))
+            );
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(250);
+            assert_eq!(sh34.get_minting_status(), MintingStatus::Phase(1));
+            assert_eq!(
+                sh34.set_presale_start_at(210),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::PhaseAlreadyRunning.as_str()
+                ))
+            );
+            assert_eq!(
+                sh34.set_public_sale_start_at(250),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidSchedule.as_str()))
+            );
+            assert!(sh34.set_public_sale_start_at(400).is_ok());
+            assert!(sh34.set_public_sale_end_at(None).is_ok());
+            assert_eq!(sh34.get_public_sale_end_at(), None);
+
+            assert_eq!(5, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn set_phases_rejects_invalid_schedule() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let unordered = Vec::from([
+                phase("b", 200, None, PRICE, false),
+                phase("a", 100, None, PRICE, false),
+            ]);
+            let overlapping = Vec::from([
+                phase("a", 100, Some(300), PRICE, false),
+                phase("b", 200, None, PRICE, false),
+            ]);
+            let ends_before_start = Vec::from([phase("a", 100, Some(100), PRICE, false)]);
+            for phases in [unordered, overlapping, ends_before_start] {
+                assert_eq!(
+                    sh34.set_phases(phases),
+                    Err(PSP34Error::Custom(Shiden34Error::InvalidSchedule.as_str()))
+                );
+            }
+            assert_eq!(sh34.get_phases().len(), 3);
+        }
+
+        #[ink::test]
+        fn pause_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert!(sh34.pause().is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
+            assert!(sh34.mint_next().is_ok());
+            let token_id = sh34.owners_token_by_index(accounts.alice, 0).unwrap();
+
+            assert!(sh34.pause().is_ok());
+            assert!(sh34.paused());
+            assert!(sh34.pause().is_err());
+            assert!(sh34.mint_next().is_err());
+            assert!(sh34
+                .transfer(accounts.bob, token_id.clone(), Vec::new())
+                .is_err());
+            assert!(sh34
+                .approve(accounts.bob, Some(token_id.clone()), true)
+                .is_err());
+
+            set_sender(accounts.charlie);
+            assert!(sh34.withdraw_project().is_err());
+            // refunds are not halted, only their own checks apply
+            assert_eq!(
+                sh34.claim_raffle_refund(2),
+                Err(PSP34Error::Custom(Shiden34Error::RaffleNotDrawn.as_str()))
+            );
+            assert_eq!(
+                sh34.claim_refund(Vec::from([token_id.clone()])),
+                Err(PSP34Error::Custom(Shiden34Error::SaleNotFailed.as_str()))
+            );
+
+            set_sender(accounts.alice);
+            assert!(sh34.unpause().is_ok());
+            assert!(!sh34.paused());
+            assert!(sh34.transfer(accounts.bob, token_id, Vec::new()).is_ok());
+            assert_eq!(sh34.balance_of(accounts.bob), 1);
+
+            // forced status, mint, paused, unpaused and transfer
+            assert_eq!(5, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn withdraw_launchpad_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            assert_eq!(sh34.owner(), accounts.alice);
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(1)))
+                .is_ok()); // presale
+            assert!(sh34.add_account_to_presale(accounts.bob, 1).is_ok());
+
+            set_sender(accounts.bob);
+
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.bob, PRESALE_PRICE);
+            assert!(pay_with_call!(sh34.mint_next(), PRESALE_PRICE).is_ok());
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_minting_status(Some(MintingStatus::End)).is_ok());
+
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.django, 0);
+
+            // after mint  withdraw
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(PUBLIC_SALE_END_AT + 1);
+
+            set_sender(accounts.django);
+            assert!(sh34.withdraw_launchpad().is_ok());
+
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.django)
+                    .ok()
+                    .unwrap(),
+                (PRESALE_PRICE * 10) / 100
+            );
+
+            assert_eq!(4, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn withdraw_project_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            assert_eq!(sh34.owner(), accounts.alice);
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(1)))
+                .is_ok()); // presale
+            assert!(sh34.add_account_to_presale(accounts.bob, 1).is_ok());
+
+            set_sender(accounts.bob);
+
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.bob, PRESALE_PRICE);
+            assert!(pay_with_call!(sh34.mint_next(), PRESALE_PRICE).is_ok());
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_minting_status(Some(MintingStatus::End)).is_ok());
+
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie, 0);
+
+            // after mint withdraw
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(PUBLIC_SALE_END_AT + 1);
+
+            set_sender(accounts.charlie);
+            assert!(sh34.withdraw_project().is_ok());
+
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie)
+                    .ok()
+                    .unwrap(),
+                (PRESALE_PRICE * 90) / 100
+            );
+
+            assert_eq!(4, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn mint_multiple_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.alice);
+            let num_of_mints: u64 = 5;
+            // Set max limit to 'num_of_mints', fails to mint 'num_of_mints + 1'. Caller is contract owner
+            assert!(sh34.set_max_mint_amount(num_of_mints).is_ok());
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            assert_eq!(
+                sh34.mint(accounts.bob, num_of_mints + 1),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::TooManyTokensToMint.as_str()
+                ))
+            );
+
             assert_eq!(sh34.total_supply(), 0);
             test::set_value_transferred::<ink::env::DefaultEnvironment>(
                 PRICE * num_of_mints as u128,
             );
-            assert!(sh34.mint(accounts.bob, num_of_mints).is_ok());
-            assert_eq!(sh34.total_supply(), num_of_mints as u128);
-            assert_eq!(sh34.balance_of(accounts.bob), 5);
-            assert_eq!(5, ink::env::test::recorded_events().count());
+            assert!(sh34.mint(accounts.bob, num_of_mints).is_ok());
+            assert_eq!(sh34.total_supply(), num_of_mints as u128);
+            assert_eq!(sh34.balance_of(accounts.bob), 5);
+            assert_eq!(6, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn mint_above_u16_supply_works() {
+            let max_supply = u16::MAX as u64 * 2;
+            let mut sh34 = init_with_max_supply(max_supply);
+            let accounts = default_accounts();
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 10);
+            assert!(sh34.mint(accounts.bob, 10).is_ok());
+            assert_eq!(sh34.balance_of(accounts.bob), 10);
+
+            for index in 0..10 {
+                let token_id = match sh34.owners_token_by_index(accounts.bob, index) {
+                    Ok(Id::U64(value)) => value,
+                    _ => 0,
+                };
+                assert!(token_id >= 1 && token_id <= max_supply);
+            }
+        }
+
+        #[ink::test]
+        fn mint_drains_token_set() {
+            let max_supply = 5;
+            let mut sh34 = init_with_max_supply(max_supply);
+            let accounts = default_accounts();
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 5);
+            assert!(sh34.mint(accounts.bob, 5).is_ok());
+
+            let mut token_ids: Vec<u64> = (0..5)
+                .map(
+                    |index| match sh34.owners_token_by_index(accounts.bob, index) {
+                        Ok(Id::U64(value)) => value,
+                        _ => 0,
+                    },
+                )
+                .collect();
+            token_ids.sort();
+            assert_eq!(token_ids, vec![1, 2, 3, 4, 5]);
+
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(Shiden34Error::CollectionIsFull.as_str()))
+            );
+        }
+
+        #[ink::test]
+        fn shuffle_index_is_a_reproducible_permutation() {
+            for size in [1, 2, 5, 17, 100] {
+                let mut shuffled: Vec<u64> = (0..size)
+                    .map(|index| shuffle_index(&[7; 32], index, size))
+                    .collect();
+                let replayed: Vec<u64> = (0..size)
+                    .map(|index| shuffle_index(&[7; 32], index, size))
+                    .collect();
+                assert_eq!(shuffled, replayed);
+                shuffled.sort();
+                assert_eq!(shuffled, (0..size).collect::<Vec<u64>>());
+            }
+
+            let first: Vec<u64> = (0..100)
+                .map(|index| shuffle_index(&[7; 32], index, 100))
+                .collect();
+            let second: Vec<u64> = (0..100)
+                .map(|index| shuffle_index(&[8; 32], index, 100))
+                .collect();
+            assert_ne!(first, second);
+        }
+
+        #[ink::test]
+        fn sequential_id_assignment_works() {
+            let mut sh34 = init_with(MAX_SUPPLY, IdAssignment::Sequential, 0);
+            let accounts = default_accounts();
+            assert_eq!(sh34.get_id_assignment(), IdAssignment::Sequential);
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 3);
+            assert!(sh34.mint(accounts.bob, 3).is_ok());
+            for index in 0..3 {
+                assert_eq!(
+                    sh34.owners_token_by_index(accounts.bob, index),
+                    Ok(Id::U64(index as u64 + 1))
+                );
+            }
+        }
+
+        #[ink::test]
+        fn offset_id_assignment_works() {
+            let max_supply = 10;
+            let mut sh34 = init_with(max_supply, IdAssignment::Offset, 0);
+            let accounts = default_accounts();
+            assert_eq!(sh34.get_starting_index(), None);
+
+            // tokens are minted in order and serve the placeholder until the offset is drawn
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+            for id in 1..=max_supply {
+                assert_eq!(sh34.get_mint_id(), Ok(id));
+                assert_eq!(sh34.get_metadata_id(id), None);
+            }
+
+            // the bound secret cannot be revealed while the sale runs
+            assert_eq!(
+                sh34.reveal_randomness(METADATA_SECRET.as_bytes().to_vec()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RandomnessRevealTooEarly.as_str()
+                ))
+            );
+            assert_eq!(sh34.get_starting_index(), None);
+
+            assert!(sh34.set_minting_status(None).is_ok());
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(PUBLIC_SALE_END_AT + 1);
+            assert_eq!(sh34.get_minting_status(), MintingStatus::End);
+            assert!(sh34
+                .reveal_randomness(METADATA_SECRET.as_bytes().to_vec())
+                .is_ok());
+            let starting_index = sh34.get_starting_index().unwrap();
+
+            let mut expected_id = starting_index + 1;
+            for id in 1..=max_supply {
+                assert_eq!(sh34.get_metadata_id(id), Some(expected_id));
+                expected_id = expected_id % max_supply + 1;
+            }
+            assert_eq!(
+                sh34.token_uri(1),
+                PreludeString::from(
+                    BASE_URI.to_owned() + format!("{}.json", starting_index + 1).as_str()
+                )
+            );
+
+            // the offset is only drawn once
+            assert_eq!(
+                sh34.reveal_randomness(METADATA_SECRET.as_bytes().to_vec()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::InvalidRandomnessReveal.as_str()
+                ))
+            );
+            assert_eq!(sh34.get_starting_index(), Some(starting_index));
+        }
+
+        #[ink::test]
+        fn reduce_uniform_rejects_biased_tail() {
+            assert_eq!(reduce_uniform(&[0; 32], 2), Some(0));
+            assert_eq!(reduce_uniform(&[0; 32], 0), Some(0));
+            // 2^256 mod 3 == 1, so the single largest seed is rejected
+            assert_eq!(reduce_uniform(&[u8::MAX; 32], 2), None);
+            // 2^256 is a multiple of 2^64, nothing is rejected
+            assert_eq!(reduce_uniform(&[u8::MAX; 32], u64::MAX), Some(u64::MAX));
+        }
+
+        #[ink::test]
+        fn commit_reveal_randomness_works() {
+            let mut sh34 = init_unrevealed(MAX_SUPPLY, IdAssignment::Random, 0);
+            let accounts = default_accounts();
+            let secret = "launch secret".as_bytes().to_vec();
+
+            set_sender(accounts.bob);
+            assert!(sh34.commit_randomness(keccak256(&secret)).is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RandomnessNotCommitted.as_str()
+                ))
+            );
+
+            assert!(sh34.commit_randomness(keccak256(&secret)).is_ok());
+            assert_eq!(
+                sh34.commit_randomness(keccak256(&secret)),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RandomnessCommitmentPending.as_str()
+                ))
+            );
+
+            // the first mint binds the commitment to the metadata
+            assert!(sh34.mint_next().is_ok());
+            assert_eq!(sh34.total_supply(), 1);
+            assert_eq!(sh34.get_randomness_commitment(), None);
+            assert_eq!(sh34.get_metadata_commitment(), Some(keccak256(&secret)));
+            assert!(sh34.mint_next().is_ok());
+
+            assert_eq!(
+                sh34.reveal_randomness("wrong secret".as_bytes().to_vec()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::InvalidRandomnessReveal.as_str()
+                ))
+            );
+            assert_eq!(
+                sh34.reveal_randomness(secret.clone()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RandomnessRevealTooEarly.as_str()
+                ))
+            );
+
+            assert!(sh34.set_minting_status(Some(MintingStatus::End)).is_ok());
+            assert!(sh34.reveal_randomness(secret).is_ok());
+            let first_metadata_id = sh34.get_metadata_id(1).unwrap();
+            let second_metadata_id = sh34.get_metadata_id(2).unwrap();
+            assert!(first_metadata_id >= 1 && first_metadata_id <= MAX_SUPPLY);
+            assert_ne!(first_metadata_id, second_metadata_id);
+
+            // sequential ids draw no randomness
+            let mut sh34 = init_unrevealed(MAX_SUPPLY, IdAssignment::Sequential, 0);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+            assert!(sh34.mint_next().is_ok());
+            assert_eq!(sh34.get_metadata_id(1), Some(1));
+        }
+
+        #[ink::test]
+        fn metadata_is_unknown_when_minting() {
+            let accounts = default_accounts();
+            let max_supply = 10;
+            let mut mappings: Vec<Vec<u64>> = Vec::new();
+            for secret in ["first secret", "second secret"] {
+                let mut sh34 = init_unrevealed(max_supply, IdAssignment::Random, 0);
+                set_sender(accounts.alice);
+                assert!(sh34
+                    .set_minting_status(Some(MintingStatus::Phase(2)))
+                    .is_ok());
+                assert!(sh34.commit_randomness(keccak256(secret.as_bytes())).is_ok());
+
+                // every mint sees the same public state, which does not fix any metadata
+                set_sender(accounts.bob);
+                for id in 1..=max_supply {
+                    test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
+                    assert!(sh34.mint_next().is_ok());
+                    assert_eq!(
+                        sh34.owners_token_by_index(accounts.bob, id as u128 - 1),
+                        Ok(Id::U64(id))
+                    );
+                    assert_eq!(sh34.get_metadata_id(id), None);
+                }
+
+                // sold out, the sale is over
+                set_sender(accounts.alice);
+                assert!(sh34.set_minting_status(None).is_ok());
+                assert_eq!(sh34.get_minting_status(), MintingStatus::End);
+                assert!(sh34.reveal_randomness(secret.as_bytes().to_vec()).is_ok());
+                let mapping: Vec<u64> = (1..=max_supply)
+                    .map(|id| sh34.get_metadata_id(id).unwrap())
+                    .collect();
+                let mut sorted = mapping.clone();
+                sorted.sort();
+                assert_eq!(sorted, (1..=max_supply).collect::<Vec<u64>>());
+                mappings.push(mapping);
+            }
+            // only the secret revealed after the sale tells the metadata apart
+            assert_ne!(mappings[0], mappings[1]);
+        }
+
+        #[ink::test]
+        fn mint_project_reserve_works() {
+            let mut sh34 = init_with(5, IdAssignment::Random, 2);
+            let accounts = default_accounts();
+            assert_eq!(sh34.get_reserve_remaining(), 2);
+            assert_eq!(sh34.get_sale_supply_remaining(), 3);
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 4);
+            assert_eq!(
+                sh34.mint(accounts.bob, 4),
+                Err(PSP34Error::Custom(Shiden34Error::CollectionIsFull.as_str()))
+            );
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 3);
+            assert!(sh34.mint(accounts.bob, 3).is_ok());
+            assert_eq!(sh34.get_sale_supply_remaining(), 0);
+
+            assert!(sh34.mint_project(accounts.bob, 1).is_err());
+
+            set_sender(accounts.charlie);
+            assert_eq!(
+                sh34.mint_project(accounts.charlie, 3),
+                Err(PSP34Error::Custom(Shiden34Error::ReserveExhausted.as_str()))
+            );
+            assert!(sh34.mint_project(accounts.charlie, 2).is_ok());
+            assert_eq!(sh34.get_reserve_remaining(), 0);
+            assert_eq!(sh34.total_supply(), 5);
+        }
+
+        #[ink::test]
+        fn airdrop_works() {
+            let mut sh34 = init_with(10, IdAssignment::Random, 2);
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                sh34.airdrop(vec![(accounts.bob, 1)]),
+                Err(PSP34Error::Custom(Shiden34Error::Unauthorized.as_str()))
+            );
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .airdrop(vec![(accounts.bob, 2), (accounts.eve, 1)])
+                .is_ok());
+            assert_eq!(sh34.balance_of(accounts.bob), 2);
+            assert_eq!(sh34.balance_of(accounts.eve), 1);
+            assert_eq!(sh34.get_sale_supply_remaining(), 5);
+            assert_eq!(3, ink::env::test::recorded_events().count());
+
+            // the whole batch fails when the supply runs out partway through
+            assert_eq!(
+                sh34.airdrop(vec![(accounts.bob, 5), (accounts.eve, 1)]),
+                Err(PSP34Error::Custom(Shiden34Error::CollectionIsFull.as_str()))
+            );
+            assert_eq!(sh34.total_supply(), 3);
+
+            set_sender(accounts.charlie);
+            assert!(sh34.set_airdrop_from_reserve(true).is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_airdrop_from_reserve(true).is_ok());
+            set_sender(accounts.charlie);
+            assert!(sh34.airdrop(vec![(accounts.eve, 2)]).is_ok());
+            assert_eq!(sh34.get_reserve_remaining(), 0);
+            assert_eq!(sh34.get_sale_supply_remaining(), 5);
+        }
+
+        #[ink::test]
+        fn mint_id_works() {
+            const PREMIUM: Balance = 1_000;
+            let max_supply = 5;
+            let mut sh34 = init_with_max_supply(max_supply);
+            let accounts = default_accounts();
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+            assert!(sh34.set_mint_id_premium(PREMIUM).is_ok());
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 2);
+            assert!(sh34.mint(accounts.bob, 2).is_ok());
+
+            let chosen_id = (1..=max_supply)
+                .find(|id| sh34.is_available_to_mint(*id))
+                .unwrap();
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
+            assert_eq!(
+                sh34.mint_id(accounts.bob, chosen_id),
+                Err(PSP34Error::Custom(Shiden34Error::BadMintValue.as_str()))
+            );
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE + PREMIUM);
+            assert!(sh34.mint_id(accounts.bob, chosen_id).is_ok());
+            assert_eq!(sh34.owner_of(Id::U64(chosen_id)), Some(accounts.bob));
+            assert!(!sh34.is_available_to_mint(chosen_id));
+            assert_eq!(
+                sh34.mint_id(accounts.bob, chosen_id),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::TokenNotAvailable.as_str()
+                ))
+            );
+
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 2);
+            assert!(sh34.mint(accounts.bob, 2).is_ok());
+
+            let mut token_ids: Vec<u64> = (0..max_supply as u128)
+                .map(
+                    |index| match sh34.owners_token_by_index(accounts.bob, index) {
+                        Ok(Id::U64(value)) => value,
+                        _ => 0,
+                    },
+                )
+                .collect();
+            token_ids.sort();
+            assert_eq!(token_ids, vec![1, 2, 3, 4, 5]);
+        }
+
+        #[ink::test]
+        fn raffle_works() {
+            let mut sh34 = init_unrevealed(MAX_SUPPLY, IdAssignment::Random, 0);
+            let accounts = default_accounts();
+            let raffle_secret = "raffle secret".as_bytes().to_vec();
+            let entrants = [
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                accounts.eve,
+            ];
+
+            set_sender(accounts.alice);
+            let mut raffle = phase("raffle", 100, Some(200), 50, false);
+            raffle.raffle = true;
+            raffle.max_supply = Some(2);
+            let public = phase("public", 300, None, PRICE, false);
+            assert!(sh34.set_phases(Vec::from([raffle, public])).is_ok());
+
+            // the first entry binds the pending commitment to the raffle
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(150);
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
+            assert_eq!(
+                sh34.enter_raffle(),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RandomnessNotCommitted.as_str()
+                ))
+            );
+            set_sender(accounts.alice);
+            assert!(sh34.commit_randomness(keccak256(&raffle_secret)).is_ok());
+            for entrant in entrants {
+                set_balance(entrant, 50);
+                set_sender(entrant);
+                assert!(pay_with_call!(sh34.enter_raffle(), 50).is_ok());
+            }
+            assert_eq!(
+                sh34.enter_raffle(),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::AlreadyEnteredRaffle.as_str()
+                ))
+            );
+            set_sender(accounts.frank);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(40);
+            assert_eq!(
+                sh34.enter_raffle(),
+                Err(PSP34Error::Custom(Shiden34Error::BadMintValue.as_str()))
+            );
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
+            assert_eq!(
+                sh34.claim_raffle_refund(0),
+                Err(PSP34Error::Custom(Shiden34Error::RaffleNotDrawn.as_str()))
+            );
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.get_raffle_commitment(0),
+                Some(keccak256(&raffle_secret))
+            );
+            assert_eq!(sh34.get_randomness_commitment(), None);
+            assert!(sh34
+                .commit_randomness(keccak256(METADATA_SECRET.as_bytes()))
+                .is_ok());
+            assert_eq!(
+                sh34.reveal_randomness(raffle_secret.clone()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RandomnessRevealTooEarly.as_str()
+                ))
+            );
+            assert!(sh34.draw_raffle(0, 10).is_err());
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(250);
+            assert_eq!(sh34.get_raffle_entry_count(0), 4);
+            assert_eq!(sh34.get_raffle_winner_count(0), None);
+            assert_eq!(
+                sh34.draw_raffle(0, 10),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RandomnessNotRevealed.as_str()
+                ))
+            );
+            assert!(sh34.reveal_randomness(raffle_secret).is_ok());
+            assert!(sh34.get_raffle_seed(0).is_some());
+
+            // anyone runs the draw in batches, winner tokens are set aside from the first one
+            set_sender(accounts.frank);
+            assert!(sh34.draw_raffle(0, 1).is_ok());
+            assert_eq!(sh34.get_raffle_winner_count(0), Some(2));
+            assert_eq!(sh34.get_raffle_winners(0, 0, 10).len(), 1);
+            assert_eq!(sh34.get_phase_remaining_allocation(0), Some(0));
+            assert_eq!(sh34.get_sale_supply_remaining(), MAX_SUPPLY - 2);
+            set_sender(accounts.bob);
+            assert_eq!(
+                sh34.claim_raffle_refund(0),
+                Err(PSP34Error::Custom(Shiden34Error::RaffleNotDrawn.as_str()))
+            );
+            assert!(sh34.draw_raffle(0, 10).is_ok());
+            assert_eq!(
+                sh34.draw_raffle(0, 10),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RaffleAlreadyDrawn.as_str()
+                ))
+            );
+
+            let winners = sh34.get_raffle_winners(0, 0, 10);
+            assert_eq!(winners.len(), 2);
+            assert_eq!(sh34.get_raffle_winners(0, 1, 10), Vec::from([winners[1]]));
+            assert_eq!(sh34.total_supply(), 2);
+            assert_eq!(sh34.get_sale_supply_remaining(), MAX_SUPPLY - 2);
+
+            // anyone can replay the draw from the entries and the seed
+            assert_eq!(
+                sh34.get_raffle_entries(0, 1, 2),
+                Vec::from([entrants[1], entrants[2]])
+            );
+            let mut entries = sh34.get_raffle_entries(0, 0, 10);
+            assert_eq!(entries, Vec::from(entrants));
+            let mut shuffle = DeterministicRandomness::new(sh34.get_raffle_seed(0).unwrap());
+            for index in 0..2 {
+                let max_offset = (entries.len() - 1 - index) as u64;
+                entries.swap(index, index + shuffle.next_u64(max_offset) as usize);
+            }
+            entries.truncate(2);
+            assert_eq!(entries, winners);
+
+            for entrant in entrants {
+                set_sender(entrant);
+                if winners.contains(&entrant) {
+                    assert_eq!(sh34.balance_of(entrant), 1);
+                    assert_eq!(
+                        sh34.claim_raffle_refund(0),
+                        Err(PSP34Error::Custom(Shiden34Error::NoRaffleRefund.as_str()))
+                    );
+                } else {
+                    assert!(sh34.claim_raffle_refund(0).is_ok());
+                    assert_eq!(
+                        test::get_account_balance::<ink::env::DefaultEnvironment>(entrant)
+                            .ok()
+                            .unwrap(),
+                        50
+                    );
+                }
+            }
+        }
+
+        #[ink::test]
+        fn raffle_draw_timeout_works() {
+            let mut sh34 = init_unrevealed(MAX_SUPPLY, IdAssignment::Random, 0);
+            let accounts = default_accounts();
+            let entrants = [accounts.bob, accounts.charlie, accounts.django];
+            let raffle_secret = "raffle secret".as_bytes().to_vec();
+            assert_eq!(sh34.get_raffle_draw_timeout(), DEFAULT_RAFFLE_DRAW_TIMEOUT);
+
+            set_sender(accounts.bob);
+            assert!(sh34.set_raffle_draw_timeout(1000).is_err());
+            set_sender(accounts.alice);
+            assert!(sh34.set_raffle_draw_timeout(1000).is_ok());
+            assert_eq!(sh34.get_raffle_draw_timeout(), 1000);
+
+            // the timeout needs the raffle to close
+            let mut raffle = phase("raffle", 100, None, 50, false);
+            raffle.raffle = true;
+            assert_eq!(
+                sh34.set_phases(Vec::from([raffle.clone()])),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidSchedule.as_str()))
+            );
+            raffle.end_at = Some(200);
+            raffle.max_supply = Some(2);
+            let public = phase("public", 300, None, PRICE, false);
+            assert!(sh34.set_phases(Vec::from([raffle, public])).is_ok());
+            assert!(sh34.commit_randomness(keccak256(&raffle_secret)).is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(150);
+            for entrant in entrants {
+                set_balance(entrant, 50);
+                set_sender(entrant);
+                assert!(pay_with_call!(sh34.enter_raffle(), 50).is_ok());
+            }
+
+            // the secret is held back until the timeout passed
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1200);
+            set_sender(accounts.bob);
+            assert_eq!(
+                sh34.claim_raffle_refund(0),
+                Err(PSP34Error::Custom(Shiden34Error::RaffleNotDrawn.as_str()))
+            );
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1201);
+            set_sender(accounts.alice);
+            assert!(sh34.reveal_randomness(raffle_secret).is_ok());
+            assert!(sh34
+                .commit_randomness(keccak256(METADATA_SECRET.as_bytes()))
+                .is_ok());
+            assert_eq!(
+                sh34.draw_raffle(0, 10),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RaffleDrawExpired.as_str()
+                ))
+            );
+
+            for entrant in entrants {
+                set_sender(entrant);
+                assert!(sh34.claim_raffle_refund(0).is_ok());
+                assert_eq!(
+                    test::get_account_balance::<ink::env::DefaultEnvironment>(entrant)
+                        .ok()
+                        .unwrap(),
+                    50
+                );
+                assert_eq!(
+                    sh34.claim_raffle_refund(0),
+                    Err(PSP34Error::Custom(Shiden34Error::NoRaffleRefund.as_str()))
+                );
+            }
+            assert_eq!(sh34.get_raffle_winner_count(0), Some(0));
+            assert_eq!(
+                sh34.draw_raffle(0, 10),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RaffleAlreadyDrawn.as_str()
+                ))
+            );
+            assert_eq!(sh34.get_sale_supply_remaining(), MAX_SUPPLY);
+            assert_eq!(sh34.total_supply(), 0);
+        }
+
+        #[ink::test]
+        fn soft_cap_refund_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert!(sh34.set_min_sales(Some(PRICE * 3)).is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_min_sales(Some(PRICE * 3)).is_ok());
+            assert_eq!(sh34.get_min_sales(), Some(PRICE * 3));
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, PRICE * 2);
+            assert!(pay_with_call!(sh34.mint(accounts.bob, 2), PRICE * 2).is_ok());
+            assert_eq!(sh34.get_account_paid_amount(accounts.bob), PRICE * 2);
+            assert_eq!(
+                sh34.claim_refund(Vec::new()),
+                Err(PSP34Error::Custom(Shiden34Error::SaleNotFailed.as_str()))
+            );
+
+            set_sender(accounts.alice);
+            assert!(sh34.airdrop(Vec::from([(accounts.bob, 1)])).is_ok());
+            assert!(sh34.set_minting_status(Some(MintingStatus::End)).is_ok());
+            assert!(sh34.is_refundable());
+            assert_eq!(sh34.get_available_to_withdraw_launchpad(), 0);
+            assert_eq!(sh34.get_available_to_withdraw_project(), 0);
+            set_sender(accounts.django);
+            assert_eq!(
+                sh34.withdraw_launchpad(),
+                Err(PSP34Error::Custom(Shiden34Error::SaleFailed.as_str()))
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(
+                sh34.withdraw_project(),
+                Err(PSP34Error::Custom(Shiden34Error::SaleFailed.as_str()))
+            );
+
+            set_sender(accounts.bob);
+            let first_id = sh34.owners_token_by_index(accounts.bob, 0).unwrap();
+            let second_id = sh34.owners_token_by_index(accounts.bob, 1).unwrap();
+            let airdropped_id = sh34.owners_token_by_index(accounts.bob, 2).unwrap();
+            // an airdropped token was not paid for
+            assert_eq!(
+                sh34.claim_refund(Vec::from([airdropped_id.clone()])),
+                Err(PSP34Error::Custom(Shiden34Error::NotRefundable.as_str()))
+            );
+            assert_eq!(
+                sh34.claim_refund(Vec::from([first_id.clone(), airdropped_id.clone()])),
+                Err(PSP34Error::Custom(Shiden34Error::NotRefundable.as_str()))
+            );
+            assert_eq!(sh34.balance_of(accounts.bob), 3);
+            assert!(sh34.claim_refund(Vec::from([first_id.clone()])).is_ok());
+            assert_eq!(sh34.get_account_paid_amount(accounts.bob), PRICE);
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .ok()
+                    .unwrap(),
+                PRICE
+            );
+            assert_eq!(
+                sh34.claim_refund(Vec::from([first_id, second_id.clone()])),
+                Err(PSP34Error::TokenNotExists)
+            );
+            assert!(sh34.claim_refund(Vec::from([second_id])).is_ok());
+            assert_eq!(
+                sh34.owners_token_by_index(accounts.bob, 0),
+                Ok(airdropped_id)
+            );
+            assert_eq!(sh34.balance_of(accounts.bob), 1);
+            assert_eq!(sh34.get_account_paid_amount(accounts.bob), 0);
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .ok()
+                    .unwrap(),
+                PRICE * 2
+            );
+            assert_eq!(
+                sh34.claim_refund(Vec::new()),
+                Err(PSP34Error::Custom(Shiden34Error::BadRefundAmount.as_str()))
+            );
+
+            // the soft cap is fixed once the sale has started
+            set_sender(accounts.alice);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1);
+            assert_eq!(
+                sh34.set_min_sales(None),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::SaleAlreadyStarted.as_str()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn soft_cap_ignores_owed_rebates() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.alice);
+            let mut auction = phase("auction", 100, Some(1000), 100, false);
+            auction.dutch_auction = Some(DutchAuction {
+                floor_price: 40,
+                price_step: 10,
+                step_interval: 50,
+                rebate: true,
+            });
+            assert!(sh34.set_phases(Vec::from([auction])).is_ok());
+            assert!(sh34.set_min_sales(Some(250)).is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(101);
+            set_balance(accounts.bob, 200);
+            set_sender(accounts.bob);
+            assert!(pay_with_call!(sh34.mint(accounts.bob, 2), 200).is_ok());
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(260);
+            set_balance(accounts.charlie, 70);
+            set_sender(accounts.charlie);
+            assert!(pay_with_call!(sh34.mint_next(), 70).is_ok());
+
+            // 270 was paid but 60 of it is owed back to bob
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001);
+            assert!(sh34.is_refundable());
+        }
+
+        #[cfg(feature = "refundable")]
+        fn init_refundable(refund_shares: Vec<Percentage>) -> ParasLaunchpadContract {
+            let accounts = default_accounts();
+            ParasLaunchpadContract::new(
+                String::from("Shiden34"), // name: String,
+                String::from("SH34"),     // symbol: String,
+                String::from(BASE_URI),   // base_uri: String,
+                None,                     // placeholder_uri: Option<String>,
+                None,                     // provenance_hash: Option<String>,
+                MAX_SUPPLY,               // max_supply: u64,
+                IdAssignment::Random,     // id_assignment: IdAssignment,
+                0,                        // reserved_supply: u64,
+                PREPRESALE_PRICE,
+                PRESALE_PRICE,
+                PRICE,                    // price_per_mint: Balance,
+                0,                        // prepresale_start_at: u64,
+                0,                        // presale_start_at: u64,
+                0,                        // public_sale_start_at: u64,
+                Some(PUBLIC_SALE_END_AT), // public_sale_end_at: u64,
+                Vec::from([1000, 2000]),  // refund_periods: Vec<u64>,
+                refund_shares,            // refund_shares: Vec<Percentage>,
+                accounts.frank,           // refund_address: AccountId,
+                10,
+                accounts.charlie, // project_treasury: AccountId,
+                accounts.django,  // launchpad_treasury: AccountId,
+            )
+        }
+
+        #[cfg(feature = "refundable")]
+        #[ink::test]
+        fn refundable_constructor_works() {
+            let sh34 = init_refundable(Vec::from([100, 50]));
+            assert_eq!(sh34.get_refund_periods(), Vec::from([1000, 2000]));
+            assert_eq!(sh34.get_refund_shares(), Vec::from([100, 50]));
+            assert_eq!(sh34.get_refund_address(), default_accounts().frank);
+        }
+
+        #[cfg(feature = "refundable")]
+        #[ink::test]
+        #[should_panic]
+        fn refundable_constructor_with_increasing_shares_fails() {
+            init_refundable(Vec::from([50, 100]));
+        }
+
+        #[cfg(feature = "refundable")]
+        #[ink::test]
+        fn refund_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert!(sh34
+                .set_refund_policy(
+                    Vec::from([1000, 2000]),
+                    Vec::from([100, 50]),
+                    accounts.frank
+                )
+                .is_err());
+
+            set_sender(accounts.alice);
+            assert_eq!(sh34.get_refund_periods(), Vec::<u64>::new());
+            assert_eq!(
+                sh34.set_refund_policy(Vec::from([1000, 2000]), Vec::from([100]), accounts.frank),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::InvalidRefundPolicy.as_str()
+                ))
+            );
+            assert_eq!(
+                sh34.set_refund_policy(
+                    Vec::from([1000, 2000]),
+                    Vec::from([50, 100]),
+                    accounts.frank
+                ),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::InvalidRefundPolicy.as_str()
+                ))
+            );
+            assert!(sh34
+                .set_refund_policy(
+                    Vec::from([1000, 2000]),
+                    Vec::from([100, 50]),
+                    accounts.frank
+                )
+                .is_ok());
+            assert_eq!(sh34.get_refund_periods(), Vec::from([1000, 2000]));
+            assert_eq!(sh34.get_refund_shares(), Vec::from([100, 50]));
+            assert_eq!(sh34.get_refund_address(), accounts.frank);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, PRICE * 2);
+            assert!(pay_with_call!(sh34.mint(accounts.bob, 2), PRICE * 2).is_ok());
+            let token_ids: Vec<u64> = (0..2)
+                .map(
+                    |index| match sh34.owners_token_by_index(accounts.bob, index) {
+                        Ok(Id::U64(id)) => id,
+                        _ => panic!("bob should own two tokens"),
+                    },
+                )
+                .collect();
+            assert_eq!(sh34.get_refund_amount(token_ids[0]), PRICE);
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_minting_status(Some(MintingStatus::End)).is_ok());
+            // proceeds stay locked while tokens can be refunded
+            assert_eq!(sh34.get_available_to_withdraw_project(), 0);
+
+            set_sender(accounts.charlie);
+            assert_eq!(sh34.refund(token_ids[0]), Err(PSP34Error::NotApproved));
+
+            set_sender(accounts.bob);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(500);
+            assert_eq!(sh34.refund(token_ids[0]), Ok(PRICE));
+            assert_eq!(sh34.owner_of(Id::U64(token_ids[0])), Some(accounts.frank));
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .ok()
+                    .unwrap(),
+                PRICE
+            );
+            assert_eq!(sh34.get_total_refund(), PRICE);
+
+            // a refunded token cannot be refunded again by the refund address
+            set_sender(accounts.frank);
+            assert_eq!(
+                sh34.refund(token_ids[0]),
+                Err(PSP34Error::Custom(Shiden34Error::NotRefundable.as_str()))
+            );
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.set_refund_periods(Vec::from([900, 2000])),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::SaleAlreadyStarted.as_str()
+                ))
+            );
+            assert!(sh34.set_refund_periods(Vec::from([1000, 3000])).is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1500);
+            assert_eq!(sh34.get_refund_amount(token_ids[1]), PRICE / 2);
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3001);
+            set_sender(accounts.bob);
+            assert_eq!(
+                sh34.refund(token_ids[1]),
+                Err(PSP34Error::Custom(Shiden34Error::RefundPeriodOver.as_str()))
+            );
+            assert_eq!(sh34.get_refund_amount(token_ids[1]), 0);
+            assert_eq!(sh34.get_available_to_withdraw_project(), PRICE * 90 / 100);
+        }
+
+        #[ink::test]
+        fn merkle_allowlist_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            let bob_leaf = allowlist_leaf(&accounts.bob, 2);
+            let charlie_leaf = allowlist_leaf(&accounts.charlie, 1);
+            let django_leaf = allowlist_leaf(&accounts.django, 3);
+            let bob_charlie_node = hash_pair(&bob_leaf, &charlie_leaf);
+            let root = hash_pair(&bob_charlie_node, &django_leaf);
+            let bob_proof = Vec::from([charlie_leaf, django_leaf]);
+
+            set_sender(accounts.bob);
+            assert!(sh34.set_allowlist_root(0, Some(root)).is_err());
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.set_allowlist_root(9, Some(root)),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidPhase.as_str()))
+            );
+            assert!(sh34.set_allowlist_root(0, Some(root)).is_ok());
+            assert_eq!(sh34.get_allowlist_root(0), Some(root));
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(0)))
+                .is_ok()); // prepresale
+
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, PREPRESALE_PRICE * 2);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE);
+            assert_eq!(
+                sh34.mint_allowlisted(accounts.bob, 1, 5, bob_proof.clone()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::InvalidAllowlistProof.as_str()
+                ))
+            );
+            assert!(pay_with_call!(
+                sh34.mint_allowlisted(accounts.bob, 1, 2, bob_proof.clone()),
+                PREPRESALE_PRICE
+            )
+            .is_ok());
+            assert_eq!(
+                sh34.get_account_allowlist_claimed_amount(0, accounts.bob),
+                1
+            );
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE * 2);
+            assert_eq!(
+                sh34.mint_allowlisted(accounts.bob, 2, 2, bob_proof.clone()),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
+            assert!(pay_with_call!(
+                sh34.mint_allowlisted(accounts.bob, 1, 2, bob_proof.clone()),
+                PREPRESALE_PRICE
+            )
+            .is_ok());
+            assert_eq!(sh34.balance_of(accounts.bob), 2);
+
+            // a proof only holds for the account of its leaf
+            set_sender(accounts.charlie);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE);
+            assert_eq!(
+                sh34.mint_allowlisted(accounts.charlie, 1, 2, bob_proof),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::InvalidAllowlistProof.as_str()
+                ))
+            );
+
+            set_sender(accounts.django);
+            set_balance(accounts.django, PREPRESALE_PRICE * 3);
+            assert!(pay_with_call!(
+                sh34.mint_allowlisted(accounts.django, 3, 3, Vec::from([bob_charlie_node])),
+                PREPRESALE_PRICE * 3
+            )
+            .is_ok());
+            assert_eq!(sh34.balance_of(accounts.django), 3);
+            // the stored allowlist is untouched by the Merkle allowlist
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE);
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
+        }
+
+        thread_local! {
+            /// Tokens of partner collections as `(collection, owner, id)`
+            static PARTNER_TOKENS: RefCell<Vec<(AccountId, AccountId, Id)>> = RefCell::new(Vec::new());
+        }
+
+        pub(super) fn partner_token_owner(collection: AccountId, id: Id) -> Option<AccountId> {
+            PARTNER_TOKENS.with(|tokens| {
+                tokens
+                    .borrow()
+                    .iter()
+                    .find(|(token_collection, _, token_id)| {
+                        *token_collection == collection && *token_id == id
+                    })
+                    .map(|(_, owner, _)| *owner)
+            })
+        }
+
+        fn give_partner_token(collection: AccountId, owner: AccountId, id: Id) {
+            PARTNER_TOKENS.with(|tokens| {
+                let mut tokens = tokens.borrow_mut();
+                tokens.retain(|(token_collection, _, token_id)| {
+                    !(*token_collection == collection && *token_id == id)
+                });
+                tokens.push((collection, owner, id));
+            });
+        }
+
+        fn voucher_signer_key(seed: u8) -> SecretKey {
+            SecretKey::from_slice(&[seed; 32]).unwrap()
+        }
+
+        fn voucher_signer_public_key(secret_key: &SecretKey) -> [u8; 33] {
+            PublicKey::from_secret_key(&Secp256k1::new(), secret_key).serialize()
+        }
+
+        fn sign_voucher(secret_key: &SecretKey, voucher: &MintVoucher) -> [u8; 65] {
+            let contract = test::callee::<ink::env::DefaultEnvironment>();
+            let message = Message::from_slice(&voucher_message_hash(&contract, voucher)).unwrap();
+            let signature: RecoverableSignature =
+                Secp256k1::new().sign_ecdsa_recoverable(&message, secret_key);
+            let (recovery_id, compact) = signature.serialize_compact();
+            let mut serialized = [0u8; 65];
+            serialized[..64].copy_from_slice(&compact);
+            serialized[64] = recovery_id.to_i32() as u8;
+            serialized
+        }
+
+        #[ink::test]
+        fn voucher_mint_keeps_auction_rebates() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            let signer_key = voucher_signer_key(7);
+            let voucher = MintVoucher {
+                account: accounts.bob,
+                phase_id: 0,
+                max_amount: 1,
+                price: 10,
+                expires_at: 2000,
+                nonce: 1,
+            };
+
+            set_sender(accounts.alice);
+            let mut auction = phase("auction", 100, Some(1000), 100, false);
+            auction.dutch_auction = Some(DutchAuction {
+                floor_price: 40,
+                price_step: 10,
+                step_interval: 50,
+                rebate: true,
+            });
+            assert!(sh34.set_phases(Vec::from([auction])).is_ok());
+            assert!(sh34
+                .set_voucher_signer(Some(voucher_signer_public_key(&signer_key)))
+                .is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(101);
+            set_balance(accounts.charlie, 100);
+            set_sender(accounts.charlie);
+            assert!(pay_with_call!(sh34.mint_next(), 100).is_ok());
+            set_balance(accounts.bob, 10);
+            set_sender(accounts.bob);
+            let signature = sign_voucher(&signer_key, &voucher);
+            assert!(pay_with_call!(sh34.mint_with_voucher(voucher, signature, 1), 10).is_ok());
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(350);
+            assert_eq!(sh34.price(), 50);
+            set_balance(accounts.django, 50);
+            set_sender(accounts.django);
+            assert!(pay_with_call!(sh34.mint_next(), 50).is_ok());
+
+            // the voucher mint neither sets the clearing price nor counts as an auction mint
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001);
+            assert_eq!(sh34.get_rebate_amount(0, accounts.charlie), 50);
+            assert_eq!(sh34.get_rebate_amount(0, accounts.bob), 0);
+            assert_eq!(sh34.get_rebate_amount(0, accounts.django), 0);
+            assert_eq!(sh34.get_available_to_withdraw_project(), 99);
+            set_sender(accounts.charlie);
+            assert!(sh34.claim_rebate(0).is_ok());
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie)
+                    .ok()
+                    .unwrap(),
+                50
+            );
+        }
+
+        #[ink::test]
+        fn voucher_mint_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            let signer_key = voucher_signer_key(7);
+            let voucher = MintVoucher {
+                account: accounts.bob,
+                phase_id: 0,
+                max_amount: 2,
+                price: PREPRESALE_PRICE / 2,
+                expires_at: 1000,
+                nonce: 1,
+            };
+
+            set_sender(accounts.bob);
+            assert!(sh34
+                .set_voucher_signer(Some(voucher_signer_public_key(&signer_key)))
+                .is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_voucher_signer(Some(voucher_signer_public_key(&signer_key)))
+                .is_ok());
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(0)))
+                .is_ok()); // prepresale, bob has no allowlist slots
+
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, PREPRESALE_PRICE * 2);
+            let signature = sign_voucher(&signer_key, &voucher);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE);
+            assert_eq!(
+                sh34.mint_with_voucher(voucher.clone(), signature, 3),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::TooManyTokensToMint.as_str()
+                ))
+            );
+            // the price is part of what was signed
+            let mut cheaper_voucher = voucher.clone();
+            cheaper_voucher.price = 1;
+            assert_eq!(
+                sh34.mint_with_voucher(cheaper_voucher, signature, 2),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidVoucher.as_str()))
+            );
+            assert!(pay_with_call!(
+                sh34.mint_with_voucher(voucher.clone(), signature, 2),
+                PREPRESALE_PRICE
+            )
+            .is_ok());
+            assert_eq!(sh34.balance_of(accounts.bob), 2);
+            assert!(sh34.is_voucher_nonce_used(1));
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PREPRESALE_PRICE / 2);
+            assert_eq!(
+                sh34.mint_with_voucher(voucher.clone(), signature, 1),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::VoucherAlreadyUsed.as_str()
+                ))
+            );
+
+            // a voucher is only valid for its account
+            set_sender(accounts.charlie);
+            let mut next_voucher = voucher.clone();
+            next_voucher.nonce = 2;
+            let next_signature = sign_voucher(&signer_key, &next_voucher);
+            assert_eq!(
+                sh34.mint_with_voucher(next_voucher.clone(), next_signature, 1),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidVoucher.as_str()))
+            );
+
+            // vouchers of a rotated out signer are rejected
+            set_sender(accounts.alice);
+            let new_signer_key = voucher_signer_key(8);
+            assert!(sh34
+                .set_voucher_signer(Some(voucher_signer_public_key(&new_signer_key)))
+                .is_ok());
+            set_sender(accounts.bob);
+            assert_eq!(
+                sh34.mint_with_voucher(next_voucher.clone(), next_signature, 1),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidVoucher.as_str()))
+            );
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001);
+            assert_eq!(
+                sh34.mint_with_voucher(
+                    next_voucher.clone(),
+                    sign_voucher(&new_signer_key, &next_voucher),
+                    1
+                ),
+                Err(PSP34Error::Custom(Shiden34Error::VoucherExpired.as_str()))
+            );
+        }
+
+        #[ink::test]
+        fn allowlist_enumeration_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.alice);
+            assert!(sh34
+                .add_account_to_allowlist_batch(
+                    1,
+                    Vec::from([
+                        (accounts.bob, 1),
+                        (accounts.charlie, 2),
+                        (accounts.django, 3)
+                    ])
+                )
+                .is_ok());
+            // adding to an account keeps a single entry
+            assert!(sh34.add_account_to_presale(accounts.bob, 3).is_ok());
+            assert_eq!(sh34.get_allowlist_count(1), 3);
+            assert_eq!(sh34.get_allowlist_count(0), 0);
+            assert_eq!(
+                sh34.get_allowlist(1, 0, 10),
+                Vec::from([
+                    (accounts.bob, 4),
+                    (accounts.charlie, 2),
+                    (accounts.django, 3)
+                ])
+            );
+            assert_eq!(
+                sh34.get_allowlist(1, 1, 1),
+                Vec::from([(accounts.charlie, 2)])
+            );
+            assert_eq!(sh34.get_allowlist(1, 5, 10), Vec::new());
+
+            set_sender(accounts.bob);
+            assert!(sh34
+                .remove_account_from_allowlist(1, accounts.charlie)
+                .is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34.remove_account_from_allowlist(1, accounts.bob).is_ok());
+            assert_eq!(
+                sh34.get_account_allowlist_minting_amount(1, accounts.bob),
+                None
+            );
+            assert_eq!(
+                sh34.get_allowlist(1, 0, 10),
+                Vec::from([(accounts.django, 3), (accounts.charlie, 2)])
+            );
+            assert_eq!(
+                sh34.remove_account_from_allowlist(1, accounts.bob),
+                Err(PSP34Error::Custom(Shiden34Error::NotAllowlisted.as_str()))
+            );
+            assert!(sh34
+                .remove_account_from_allowlist_batch(
+                    1,
+                    Vec::from([accounts.charlie, accounts.django])
+                )
+                .is_ok());
+            assert_eq!(sh34.get_allowlist_count(1), 0);
+
+            // three additions, one update and three removals
+            assert_eq!(7, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn allowlist_updates_work() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert!(sh34
+                .set_account_allowlist_minting_amount(1, accounts.bob, 5)
+                .is_err());
+            assert!(sh34
+                .increase_account_allowlist_minting_amount(1, accounts.bob, 5)
+                .is_err());
+            assert!(sh34
+                .decrease_account_allowlist_minting_amount(1, accounts.bob, 5)
+                .is_err());
+
+            set_sender(accounts.alice);
+            // adding again keeps the slots left instead of overwriting them
+            assert!(sh34.add_account_to_presale(accounts.bob, 2).is_ok());
+            assert!(sh34.add_account_to_presale(accounts.bob, 3).is_ok());
+            assert_eq!(
+                sh34.get_account_presale_minting_amount(accounts.bob),
+                Some(5)
+            );
+
+            assert!(sh34
+                .decrease_account_allowlist_minting_amount(1, accounts.bob, 4)
+                .is_ok());
+            assert_eq!(
+                sh34.decrease_account_allowlist_minting_amount(1, accounts.bob, 2),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::BadAllowlistAmount.as_str()
+                ))
+            );
+            assert_eq!(
+                sh34.decrease_account_allowlist_minting_amount(1, accounts.charlie, 1),
+                Err(PSP34Error::Custom(Shiden34Error::NotAllowlisted.as_str()))
+            );
+            assert!(sh34
+                .increase_account_allowlist_minting_amount(1, accounts.bob, 2)
+                .is_ok());
+            assert_eq!(
+                sh34.get_account_presale_minting_amount(accounts.bob),
+                Some(3)
+            );
+            assert_eq!(
+                sh34.increase_account_allowlist_minting_amount(1, accounts.bob, u64::MAX),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::BadAllowlistAmount.as_str()
+                ))
+            );
+            assert!(sh34
+                .set_account_allowlist_minting_amount(1, accounts.bob, 1)
+                .is_ok());
+            assert_eq!(
+                sh34.get_account_presale_minting_amount(accounts.bob),
+                Some(1)
+            );
+            assert!(sh34
+                .set_account_allowlist_minting_amount(3, accounts.bob, 1)
+                .is_err());
+            assert_eq!(sh34.get_allowlist(1, 0, 10), Vec::from([(accounts.bob, 1)]));
+
+            // each successful update is recorded with the old and new amounts
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(5, events.len());
+            let amounts = events
+                .iter()
+                .map(|event| {
+                    match <Event as scale::Decode>::decode(&mut &event.data[..]).unwrap() {
+                        Event::AllowlistUpdated(AllowlistUpdated {
+                            phase_id,
+                            account,
+                            old_amount,
+                            new_amount,
+                        }) => {
+                            assert_eq!(phase_id, 1);
+                            assert_eq!(account, accounts.bob);
+                            (old_amount, new_amount)
+                        }
+                        _ => panic!("unexpected event"),
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                amounts,
+                Vec::from([
+                    (None, Some(2)),
+                    (Some(2), Some(5)),
+                    (Some(5), Some(1)),
+                    (Some(1), Some(3)),
+                    (Some(3), Some(1)),
+                ])
+            );
+        }
+
+        #[ink::test]
+        fn token_gates_work() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            let gate = TokenGate {
+                collection: accounts.frank,
+                slots_per_token: 2,
+            };
+
+            set_sender(accounts.bob);
+            assert!(sh34.set_token_gates(1, Vec::from([gate.clone()])).is_err());
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.set_token_gates(
+                    1,
+                    Vec::from([TokenGate {
+                        collection: accounts.frank,
+                        slots_per_token: 0,
+                    }])
+                ),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidTokenGate.as_str()))
+            );
+            assert!(sh34.set_token_gates(5, Vec::from([gate.clone()])).is_err());
+            assert!(sh34.set_token_gates(1, Vec::from([gate.clone()])).is_ok());
+            assert_eq!(sh34.get_token_gates(1), Vec::from([gate.clone()]));
+            assert_eq!(sh34.get_token_gates(0), Vec::new());
+
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(1)))
+                .is_ok());
+            assert!(sh34.add_account_to_presale(accounts.bob, 1).is_ok());
+            give_partner_token(accounts.frank, accounts.bob, Id::U64(1));
+            give_partner_token(accounts.frank, accounts.bob, Id::U64(2));
+
+            let frank_tokens = |ids: &[u64]| -> Vec<(AccountId, Id)> {
+                ids.iter()
+                    .map(|id| (accounts.frank, Id::U64(*id)))
+                    .collect()
+            };
+
+            // partner tokens are only used when the caller lists them
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE * 4);
+            assert_eq!(
+                sh34.mint(accounts.bob, 4),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
+
+            // listed tokens must be gated, listed once, owned by the caller and at most MAX_GATED_TOKENS
+            assert_eq!(
+                sh34.mint_gated(accounts.bob, 4, frank_tokens(&[1, 1])),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidTokenGate.as_str()))
+            );
+            assert_eq!(
+                sh34.mint_gated(accounts.bob, 4, Vec::from([(accounts.eve, Id::U64(1))])),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidTokenGate.as_str()))
+            );
+            give_partner_token(accounts.frank, accounts.charlie, Id::U64(3));
+            assert_eq!(
+                sh34.mint_gated(accounts.bob, 4, frank_tokens(&[3])),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::GatedTokenNotOwned.as_str()
+                ))
+            );
+            assert_eq!(
+                sh34.mint_gated(accounts.bob, 4, frank_tokens(&[4])),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::GatedTokenNotOwned.as_str()
+                ))
+            );
+            let too_many: Vec<u64> = (1..=MAX_GATED_TOKENS as u64 + 1).collect();
+            assert_eq!(
+                sh34.mint_gated(accounts.bob, 4, frank_tokens(&too_many)),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::TooManyGatedTokens.as_str()
+                ))
+            );
+
+            // allowlist slots are used first, partner tokens make up for the rest
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE * 6);
+            assert_eq!(
+                sh34.mint_gated(accounts.bob, 6, frank_tokens(&[1, 2])),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE * 4);
+            assert!(sh34
+                .mint_gated(accounts.bob, 4, frank_tokens(&[1, 2]))
+                .is_ok());
+            assert_eq!(sh34.balance_of(accounts.bob), 4);
+            assert_eq!(
+                sh34.get_account_presale_minting_amount(accounts.bob),
+                Some(0)
+            );
+            assert_eq!(
+                sh34.get_gated_token_claimed_amount(1, accounts.frank, Id::U64(1)),
+                2
+            );
+            assert_eq!(
+                sh34.get_gated_token_claimed_amount(1, accounts.frank, Id::U64(2)),
+                1
+            );
+            assert_eq!(
+                sh34.get_gated_token_claimed_amount(0, accounts.frank, Id::U64(2)),
+                0
+            );
+
+            // a partner token gives its slots once, whichever wallet holds it
+            give_partner_token(accounts.frank, accounts.charlie, Id::U64(1));
+            set_sender(accounts.charlie);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRESALE_PRICE);
+            assert_eq!(
+                sh34.mint_gated(accounts.charlie, 1, frank_tokens(&[1])),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
+            give_partner_token(accounts.frank, accounts.charlie, Id::U64(2));
+            assert!(sh34
+                .mint_gated(accounts.charlie, 1, frank_tokens(&[1, 2]))
+                .is_ok());
+            assert_eq!(
+                sh34.mint_gated(accounts.charlie, 1, frank_tokens(&[1, 2])),
+                Err(PSP34Error::Custom(Shiden34Error::UnableToMint.as_str()))
+            );
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_token_gates(1, Vec::new()).is_ok());
+            assert_eq!(sh34.get_token_gates(1), Vec::new());
+        }
+
+        #[ink::test]
+        fn token_auction_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+
+            set_sender(accounts.bob);
+            assert!(sh34.create_token_auction(7, 100, 1000).is_err());
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.create_token_auction(7, 100, 0),
+                Err(PSP34Error::Custom(Shiden34Error::InvalidAuction.as_str()))
+            );
+            assert!(sh34.create_token_auction(7, 100, 1000).is_ok());
+            assert!(!sh34.is_available_to_mint(7));
+            assert_eq!(sh34.get_sale_supply_remaining(), MAX_SUPPLY - 1);
+            assert_eq!(
+                sh34.create_token_auction(7, 100, 1000),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::TokenNotAvailable.as_str()
+                ))
+            );
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
+            assert_eq!(
+                sh34.bid(7),
+                Err(PSP34Error::Custom(Shiden34Error::BidTooLow.as_str()))
+            );
+            set_balance(accounts.bob, 100);
+            assert!(pay_with_call!(sh34.bid(7), 100).is_ok());
+
+            set_sender(accounts.charlie);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(
+                sh34.bid(7),
+                Err(PSP34Error::Custom(Shiden34Error::BidTooLow.as_str()))
+            );
+            set_balance(accounts.charlie, 150);
+            assert!(pay_with_call!(sh34.bid(7), 150).is_ok());
+            // the outbid bidder withdraws the escrow
+            assert_eq!(sh34.get_bid_refund(accounts.bob), 100);
+            assert_eq!(
+                sh34.withdraw_bid_refund(),
+                Err(PSP34Error::Custom(Shiden34Error::NoBidRefund.as_str()))
+            );
+            set_sender(accounts.bob);
+            assert!(sh34.withdraw_bid_refund().is_ok());
+            assert_eq!(sh34.get_bid_refund(accounts.bob), 0);
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .ok()
+                    .unwrap(),
+                100
+            );
+            assert_eq!(
+                sh34.withdraw_bid_refund(),
+                Err(PSP34Error::Custom(Shiden34Error::NoBidRefund.as_str()))
+            );
+
+            set_sender(accounts.charlie);
+            assert_eq!(
+                sh34.settle_token_auction(7),
+                Err(PSP34Error::Custom(Shiden34Error::AuctionNotEnded.as_str()))
+            );
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(200);
+            assert_eq!(
+                sh34.bid(7),
+                Err(PSP34Error::Custom(Shiden34Error::AuctionEnded.as_str()))
+            );
+            assert!(sh34.settle_token_auction(7).is_ok());
+            assert_eq!(sh34.owner_of(Id::U64(7)), Some(accounts.charlie));
+            assert_eq!(sh34.get_token_auction(7), None);
+
+            // an auction without bids puts the token back on sale
+            set_sender(accounts.alice);
+            assert!(sh34.create_token_auction(8, 100, 2000).is_ok());
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(2001);
+            assert!(sh34.settle_token_auction(8).is_ok());
+            assert!(sh34.is_available_to_mint(8));
+            assert_eq!(sh34.get_sale_supply_remaining(), MAX_SUPPLY - 1);
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(PUBLIC_SALE_END_AT + 1);
+            assert_eq!(sh34.get_available_to_withdraw_project(), 135);
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
+            assert!(sh34.mint_next().is_ok());
+            let token_id = sh34.owners_token_by_index(accounts.bob, 0).unwrap();
+
+            set_sender(accounts.charlie);
+            assert_eq!(sh34.burn(token_id.clone()), Err(PSP34Error::NotApproved));
+
+            set_sender(accounts.bob);
+            assert!(sh34.burn(token_id.clone()).is_ok());
+            assert_eq!(sh34.owner_of(token_id.clone()), None);
+            assert_eq!(sh34.balance_of(accounts.bob), 0);
+            assert_eq!(sh34.burn(token_id), Err(PSP34Error::TokenNotExists));
+            assert_eq!(3, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn redeem_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            set_sender(accounts.bob);
+            assert!(sh34.add_redemption_tier(2, 1).is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34.add_redemption_tier(2, 1).is_ok());
+            assert_eq!(
+                sh34.get_redemption_tiers()[0].first_token_id,
+                MAX_SUPPLY + 1
+            );
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 4);
+            assert!(sh34.mint(accounts.bob, 4).is_ok());
+            let token_ids: Vec<Id> = (0..4)
+                .map(|index| sh34.owners_token_by_index(accounts.bob, index).unwrap())
+                .collect();
+
+            assert_eq!(
+                sh34.redeem(1, token_ids[..2].to_vec()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::InvalidRedemptionTier.as_str()
+                ))
+            );
+            assert_eq!(
+                sh34.redeem(0, token_ids[..1].to_vec()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::BadRedemptionAmount.as_str()
+                ))
+            );
+            assert_eq!(
+                sh34.redeem(0, vec![token_ids[0].clone(), token_ids[0].clone()]),
+                Err(PSP34Error::NotApproved)
+            );
+
+            assert!(sh34.redeem(0, token_ids[..2].to_vec()).is_ok());
+            assert_eq!(sh34.owner_of(token_ids[0].clone()), None);
+            assert_eq!(sh34.owner_of(Id::U64(MAX_SUPPLY + 1)), Some(accounts.bob));
+            assert_eq!(sh34.balance_of(accounts.bob), 3);
+            assert_eq!(sh34.get_redemption_tiers()[0].minted, 1);
+
+            assert_eq!(
+                sh34.redeem(0, token_ids[2..].to_vec()),
+                Err(PSP34Error::Custom(
+                    Shiden34Error::RedemptionTierFull.as_str()
+                ))
+            );
+            // forced status, 4 mints, 2 burns, 1 redeemed mint and the Redeemed event
+            assert_eq!(9, ink::env::test::recorded_events().count());
+        }
+
+        #[ink::test]
+        fn redeemed_tokens_are_not_refunded() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            let mut public = phase("public", 0, None, PRICE, false);
+            public.max_supply = Some(4);
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_phases(Vec::from([public])).is_ok());
+            assert!(sh34.set_min_sales(Some(PRICE * 10)).is_ok());
+            assert!(sh34.add_redemption_tier(2, 1).is_ok());
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1);
+
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, PRICE * 3);
+            assert!(pay_with_call!(sh34.mint(accounts.bob, 3), PRICE * 3).is_ok());
+            assert_eq!(sh34.get_phase_remaining_allocation(0), Some(1));
+            let token_ids: Vec<Id> = (0..3)
+                .map(|index| sh34.owners_token_by_index(accounts.bob, index).unwrap())
+                .collect();
+            assert!(sh34.redeem(0, token_ids[..2].to_vec()).is_ok());
+            // the redeemed mint takes nothing from the phase allocation
+            assert_eq!(sh34.get_phase_remaining_allocation(0), Some(1));
+            assert_eq!(sh34.get_phase_minted_amount(0), 3);
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_minting_status(Some(MintingStatus::End)).is_ok());
+            assert!(sh34.is_refundable());
+
+            set_sender(accounts.bob);
+            let redeemed_id = Id::U64(MAX_SUPPLY + 1);
+            assert_eq!(
+                sh34.claim_refund(Vec::from([redeemed_id.clone()])),
+                Err(PSP34Error::Custom(Shiden34Error::NotRefundable.as_str()))
+            );
+            assert_eq!(
+                sh34.claim_refund(Vec::from([token_ids[0].clone()])),
+                Err(PSP34Error::TokenNotExists)
+            );
+            assert!(sh34.claim_refund(Vec::from([token_ids[2].clone()])).is_ok());
+            assert_eq!(sh34.owner_of(redeemed_id), Some(accounts.bob));
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .ok()
+                    .unwrap(),
+                PRICE
+            );
         }
 
         #[ink::test]
@@ -476,7 +3095,9 @@ pub mod paras_launchpad {
             let mut sh34 = init();
             let accounts = default_accounts();
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(3)).is_ok());
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
 
             set_sender(accounts.bob);
             let num_of_mints = 1;
@@ -499,13 +3120,48 @@ pub mod paras_launchpad {
             assert_eq!(sh34.total_supply(), 0);
         }
 
+        #[ink::test]
+        fn mint_overpayment_refund_works() {
+            let mut sh34 = init();
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE * 2);
+            assert_eq!(
+                sh34.mint_next(),
+                Err(PSP34Error::Custom(Shiden34Error::BadMintValue.as_str()))
+            );
+
+            set_sender(accounts.alice);
+            assert!(sh34.set_accept_overpayment(true).is_ok());
+            assert!(sh34.get_accept_overpayment());
+
+            set_balance(accounts.bob, PRICE * 2);
+            set_sender(accounts.bob);
+            assert!(pay_with_call!(sh34.mint_next(), PRICE * 2).is_ok());
+            assert_eq!(sh34.balance_of(accounts.bob), 1);
+            assert_eq!(
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .ok()
+                    .unwrap(),
+                PRICE
+            );
+            assert_eq!(sh34.env().balance(), PRICE + sh34.env().minimum_balance());
+        }
+
         #[ink::test]
         fn withdrawal_works() {
             let mut sh34 = init();
             let accounts = default_accounts();
 
             set_sender(accounts.alice);
-            assert!(sh34.set_minting_status(Some(3)).is_ok());
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
 
             set_balance(accounts.bob, PRICE);
             set_sender(accounts.bob);
@@ -532,7 +3188,9 @@ pub mod paras_launchpad {
             let accounts = default_accounts();
             set_sender(accounts.alice);
 
-            assert!(sh34.set_minting_status(Some(3)).is_ok());
+            assert!(sh34
+                .set_minting_status(Some(MintingStatus::Phase(2)))
+                .is_ok());
             test::set_value_transferred::<ink::env::DefaultEnvironment>(PRICE);
             let mint_result = sh34.mint_next();
             assert!(mint_result.is_ok());
@@ -543,11 +3201,12 @@ pub mod paras_launchpad {
                     U64(value) => value,
                     _ => 0,
                 };
+            assert_eq!(sh34.get_metadata_id(alice_token_id), None);
+            reveal_metadata(&mut sh34);
+            let metadata_id = sh34.get_metadata_id(alice_token_id).unwrap();
             assert_eq!(
                 sh34.token_uri(alice_token_id),
-                PreludeString::from(
-                    BASE_URI.to_owned() + format!("{}.json", alice_token_id).as_str()
-                )
+                PreludeString::from(BASE_URI.to_owned() + format!("{}.json", metadata_id).as_str())
             );
 
             // verify token_uri when baseUri is empty
@@ -555,8 +3214,120 @@ pub mod paras_launchpad {
             assert!(sh34.set_base_uri(PreludeString::from("")).is_ok());
             assert_eq!(
                 sh34.token_uri(alice_token_id),
-                PreludeString::from("".to_owned() + format!("{}.json", alice_token_id).as_str())
+                PreludeString::from("".to_owned() + format!("{}.json", metadata_id).as_str())
+            );
+        }
+
+        #[ink::test]
+        fn delayed_reveal_works() {
+            const PLACEHOLDER_URI: &str = "ipfs://placeholder.json";
+            const REVEALED_URI: &str = "ipfs://revealed/";
+            let accounts = default_accounts();
+            let mut sh34 = new_contract(
+                String::from("Shiden34"),            // name: String,
+                String::from("SH34"),                // symbol: String,
+                String::new(),                       // base_uri: String,
+                Some(String::from(PLACEHOLDER_URI)), // placeholder_uri: Option<String>,
+                Some(String::from("provenance")),    // provenance_hash: Option<String>,
+                MAX_SUPPLY,                          // max_supply: u64,
+                IdAssignment::Sequential,            // id_assignment: IdAssignment,
+                0,                                   // reserved_supply: u64,
+                PREPRESALE_PRICE,
+                PRESALE_PRICE,
+                PRICE,                    // price_per_mint: Balance,
+                0,                        // prepresale_start_at: u64,
+                0,                        // presale_start_at: u64,
+                0,                        // public_sale_start_at: u64,
+                Some(PUBLIC_SALE_END_AT), // public_sale_end_at: u64,
+                10,
+                accounts.charlie, // project_treasury: AccountId,
+                accounts.django,  // launchpad_treasury: AccountId,
+            );
+            assert!(!sh34.is_revealed());
+            let collection_id = sh34.collection_id();
+            assert_eq!(
+                sh34.get_attribute(collection_id.clone(), String::from("baseUri")),
+                None
+            );
+            assert_eq!(
+                sh34.get_provenance_hash(),
+                Some(PreludeString::from("provenance"))
+            );
+            assert_eq!(sh34.token_uri(1), PreludeString::from(PLACEHOLDER_URI));
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                sh34.set_base_uri(PreludeString::from(REVEALED_URI)),
+                Err(PSP34Error::Custom(Shiden34Error::NotRevealed.as_str()))
+            );
+
+            set_sender(accounts.bob);
+            assert!(sh34.reveal(PreludeString::from(REVEALED_URI)).is_err());
+
+            set_sender(accounts.alice);
+            assert!(sh34.reveal(PreludeString::from(REVEALED_URI)).is_ok());
+            assert!(sh34.is_revealed());
+            assert_eq!(
+                sh34.get_attribute(collection_id, String::from("baseUri")),
+                Some(String::from(REVEALED_URI))
+            );
+            assert_eq!(
+                sh34.token_uri(1),
+                PreludeString::from(REVEALED_URI.to_owned() + "1.json")
+            );
+            assert_eq!(
+                sh34.reveal(PreludeString::from(REVEALED_URI)),
+                Err(PSP34Error::Custom(Shiden34Error::AlreadyRevealed.as_str()))
             );
+            assert_eq!(1, ink::env::test::recorded_events().count());
+        }
+
+        fn init_with_placeholder(
+            base_uri: &str,
+            placeholder_uri: String,
+        ) -> ParasLaunchpadContract {
+            let accounts = default_accounts();
+            new_contract(
+                String::from("Shiden34"), // name: String,
+                String::from("SH34"),     // symbol: String,
+                String::from(base_uri),   // base_uri: String,
+                Some(placeholder_uri),    // placeholder_uri: Option<String>,
+                None,                     // provenance_hash: Option<String>,
+                MAX_SUPPLY,               // max_supply: u64,
+                IdAssignment::Random,     // id_assignment: IdAssignment,
+                0,                        // reserved_supply: u64,
+                PREPRESALE_PRICE,
+                PRESALE_PRICE,
+                PRICE,                    // price_per_mint: Balance,
+                0,                        // prepresale_start_at: u64,
+                0,                        // presale_start_at: u64,
+                0,                        // public_sale_start_at: u64,
+                Some(PUBLIC_SALE_END_AT), // public_sale_end_at: u64,
+                10,
+                accounts.charlie, // project_treasury: AccountId,
+                accounts.django,  // launchpad_treasury: AccountId,
+            )
+        }
+
+        #[ink::test]
+        fn placeholder_path_works() {
+            let sh34 = init_with_placeholder("", String::from("ipfs://placeholder/"));
+            assert_eq!(
+                sh34.token_uri(7),
+                PreludeString::from("ipfs://placeholder/7.json")
+            );
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn placeholder_with_base_uri_fails() {
+            init_with_placeholder(BASE_URI, String::from("ipfs://placeholder.json"));
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn non_utf8_placeholder_fails() {
+            init_with_placeholder("", Vec::from([0xff, 0xfe]));
         }
 
         #[ink::test]
@@ -595,11 +3366,15 @@ pub mod paras_launchpad {
         fn check_supply_overflow_ok() {
             let max_supply = u64::MAX - 1;
             let accounts = default_accounts();
-            let mut sh34 = ParasLaunchpadContract::new(
+            let mut sh34 = new_contract(
                 String::from("Shiden34"), // name: String,
                 String::from("SH34"),     // symbol: String,
                 String::from(BASE_URI),   // base_uri: String,
+                None,                     // placeholder_uri: Option<String>,
+                None,                     // provenance_hash: Option<String>,
                 max_supply,               // max_supply: u64
+                IdAssignment::Random,     // id_assignment: IdAssignment,
+                0,                        // reserved_supply: u64,
                 PREPRESALE_PRICE,
                 PRESALE_PRICE,
                 PRICE,   // price_per_mint: Balance,
@@ -633,11 +3408,15 @@ pub mod paras_launchpad {
             let max_supply = u64::MAX;
             let price = u128::MAX as u128;
             let accounts = default_accounts();
-            let sh34 = ParasLaunchpadContract::new(
+            let sh34 = new_contract(
                 String::from("Shiden34"), // name: String,
                 String::from("SH34"),     // symbol: String,
                 String::from(BASE_URI),   // base_uri: String,
+                None,                     // placeholder_uri: Option<String>,
+                None,                     // provenance_hash: Option<String>,
                 max_supply,               // max_supply: u64,
+                IdAssignment::Random,     // id_assignment: IdAssignment,
+                0,                        // reserved_supply: u64,
                 PREPRESALE_PRICE,
                 PRESALE_PRICE,
                 price,                 // price_per_mint: Balance,
@@ -652,11 +3431,31 @@ pub mod paras_launchpad {
             let transferred_value = u128::MAX;
             let mint_amount = u64::MAX;
             assert_eq!(
-                sh34.check_value(transferred_value, mint_amount, &MintingStatus::Public),
+                sh34.check_value(transferred_value, mint_amount, &MintingStatus::Phase(2)),
                 Err(PSP34Error::Custom(Shiden34Error::BadMintValue.as_str()))
             );
         }
 
+        fn phase(
+            name: &str,
+            start_at: u64,
+            end_at: Option<u64>,
+            price: Balance,
+            allowlist_only: bool,
+        ) -> Phase {
+            Phase {
+                name: String::from(name),
+                start_at,
+                end_at,
+                price,
+                dutch_auction: None,
+                raffle: false,
+                allowlist_only,
+                max_per_account: None,
+                max_supply: None,
+            }
+        }
+
         fn default_accounts() -> test::DefaultAccounts<ink::env::DefaultEnvironment> {
             test::default_accounts::<Environment>()
         }
@@ -19,36 +19,132 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
-use crate::impls::launchpad::types::{Data, MintingStatus, Percentage, Shiden34Error};
+use crate::impls::launchpad::merkle::{allowlist_leaf, verify_proof};
+use crate::impls::launchpad::randomness::{
+    keccak256, shuffle_index, DeterministicRandomness, RandomnessSource,
+};
+use crate::impls::launchpad::types::{
+    Data, DutchAuction, IdAssignment, MintAuthorization, MintVoucher, MintingStatus, Percentage,
+    Phase, PhaseId, PhaseSchedule, RedemptionTier, RedemptionTierId, Shiden34Error, TokenAuction,
+    TokenGate, TokenId, MAX_GATED_TOKENS, MAX_PHASES, PREPRESALE_PHASE, PRESALE_PHASE,
+    REDEEMED_MINTING_TYPE,
+};
+use crate::impls::launchpad::voucher::voucher_message_hash;
 pub use crate::traits::launchpad::Launchpad;
 
+#[cfg(feature = "refundable")]
+use crate::impls::launchpad::refundable::Internal as RefundableInternal;
+
 use ink::prelude::vec::Vec;
 use openbrush::{
     contracts::{
         ownable::*,
+        pausable::*,
         psp34::extensions::{enumerable::*, metadata::*},
     },
     modifiers,
     traits::{AccountId, Balance, Storage, String},
 };
-
-use ink::env::{hash, hash_bytes};
+use scale::Encode;
 
 pub trait Internal {
-    /// Check if the transferred mint values is as expected
+    /// Check if the transferred mint values is as expected, returns the value to be charged
     fn check_value(
         &self,
         transferred_value: u128,
         mint_amount: u64,
         minting_status: &MintingStatus,
+    ) -> Result<Balance, PSP34Error>;
+
+    /// Check the transferred value against a price per token, returns the value to be charged
+    fn check_value_at_price(
+        &self,
+        transferred_value: u128,
+        mint_amount: u64,
+        price: Balance,
+    ) -> Result<Balance, PSP34Error>;
+
+    /// Send back the part of the transferred value which was not charged
+    fn refund_overpayment(
+        &self,
+        account_id: AccountId,
+        transferred_value: Balance,
+        mint_value: Balance,
     ) -> Result<(), PSP34Error>;
 
     /// Check amount of tokens to be minted
     fn check_amount(&self, mint_amount: u64) -> Result<(), PSP34Error>;
 
-    fn get_pseudo_random(&mut self, max_amount: u64) -> u64;
+    /// Take the next token id out of the unminted token set, ids are handed out in order and only
+    /// get their metadata once the randomness bound to the sale is revealed
+    fn get_mint_id(&mut self) -> Result<u64, PSP34Error>;
+
+    /// Bind the pending commitment to the metadata assignment on the first mint of a shuffled
+    /// collection, so that its secret cannot be revealed before the sale ends
+    fn bind_metadata_commitment(&mut self) -> Result<(), PSP34Error>;
+
+    /// Mix a mint made before the sale ended into the metadata assignment
+    fn record_mint_entropy(&mut self, token_id: TokenId);
+
+    /// Fix the metadata assignment from the secret bound to it, once nothing can be minted anymore
+    fn reveal_metadata_seed(&mut self, secret: Vec<u8>) -> Result<(), PSP34Error>;
+
+    /// Get the metadata id of a token, `None` while the randomness bound to the sale is not revealed
+    fn get_metadata_id_internal(&self, token_id: TokenId) -> Option<u64>;
+
+    /// Get the token id stored at `index` of the unminted token set
+    fn get_token_set_value(&self, index: u64) -> TokenId;
+
+    /// Remove the entry at `index` of the unminted token set, returning its token id
+    fn remove_token_set_index(&mut self, index: u64) -> TokenId;
+
+    /// Get the index of a token id in the unminted token set, `None` once it is minted
+    fn get_token_set_index(&self, token_id: TokenId) -> Option<u64>;
+
+    /// Check a given token id can be taken out of the unminted token set
+    fn check_id_choosable(&self) -> Result<(), PSP34Error>;
+
+    /// Put a token id back at the end of the unminted token set
+    fn add_token_set_value(&mut self, token_id: TokenId);
+
+    fn get_token_auction_internal(&self, id: TokenId) -> Result<TokenAuction, PSP34Error>;
+
+    fn check_raffle_phase(&self, phase_id: PhaseId) -> Result<(), PSP34Error>;
+
+    /// Get index of the entry at a position of the raffle draw shuffle
+    fn get_raffle_shuffled_index(&self, phase_id: PhaseId, position: u64) -> u64;
+
+    /// Check every winner of a raffle phase has been drawn
+    fn is_raffle_drawn(&self, phase_id: PhaseId) -> bool;
+
+    /// Check a raffle phase was not fully drawn within the timeout after it closed
+    fn is_raffle_draw_expired(&self, phase_id: PhaseId) -> bool;
+
+    /// End an expired draw with the winners drawn so far, giving back the tokens set aside for
+    /// the others so that every remaining entry can be refunded
+    fn close_raffle_draw(&mut self, phase_id: PhaseId);
+
+    /// Add a sale to `total_sales` and to what the account paid
+    fn record_payment(&mut self, account_id: AccountId, token_amount: u64, value: Balance);
+
+    /// Keep the buyer and the price paid for a sale token, so that it can be refunded
+    fn record_token_sale(&mut self, id: TokenId, buyer: AccountId, price: Balance);
+
+    /// Drop the sale record of a burned token, its price can not be refunded anymore
+    fn remove_token_sale(&mut self, id: &Id);
+
+    /// Get the price a sale token is refunded on
+    fn get_refundable_price(&self, token_id: TokenId) -> Result<Balance, PSP34Error>;
+
+    /// Check if the first phase of the schedule has started
+    fn has_sale_started(&self) -> bool;
 
-    fn get_mint_id(&mut self) -> u64;
+    /// Check if the sale ended below the minimum sales
+    fn is_sale_failed(&self) -> bool;
+
+    fn get_reserve_remaining_internal(&self) -> u64;
+
+    fn get_sale_supply_remaining_internal(&self) -> u64;
 
     fn get_available_to_withdraw_launchpad_internal(&self) -> Balance;
 
@@ -56,14 +152,156 @@ pub trait Internal {
 
     fn get_total_withdraw_share_internal(&self) -> u128;
 
+    /// Check the account may mint in the current phase and count the mint,
+    /// the authorization replaces the stored allowlist slots unless it is `Allowlist`
     fn check_and_update_allowed_to_mint(
         &mut self,
         account_id: AccountId,
         mint_amount: u64,
         minting_status: &MintingStatus,
+        authorization: &MintAuthorization,
+    ) -> Result<(), PSP34Error>;
+
+    /// Mint tokens paid by the caller, shared by the `mint*` messages minting from the token set
+    fn mint_paid(
+        &mut self,
+        to: AccountId,
+        mint_amount: u64,
+        authorization: MintAuthorization,
+    ) -> Result<(), PSP34Error>;
+
+    /// Check a voucher was signed by the voucher signer for the caller and the current phase,
+    /// and mark its nonce as used
+    fn use_voucher(
+        &mut self,
+        voucher: &MintVoucher,
+        signature: &[u8; 65],
+        mint_amount: u64,
     ) -> Result<(), PSP34Error>;
 
     fn get_current_minting_status(&self) -> MintingStatus;
+
+    fn get_phase(&self, phase_id: PhaseId) -> Result<&Phase, PSP34Error>;
+
+    /// Check that phases are ordered by start time and do not overlap
+    fn check_phases(&self, phases: &Vec<Phase>) -> Result<(), PSP34Error>;
+
+    /// Replace the schedule, keeping the phases which have started except for the end
+    /// of the running phase, and the starts of pending phases ahead
+    fn update_phases(&mut self, phases: Vec<Phase>) -> Result<(), PSP34Error>;
+
+    /// Move a phase through `update_phases`
+    fn set_phase_schedule(
+        &mut self,
+        phase_id: PhaseId,
+        start_at: u64,
+        end_at: Option<u64>,
+    ) -> Result<(), PSP34Error>;
+
+    fn get_last_phase_id(&self) -> Result<PhaseId, PSP34Error>;
+
+    /// Get price of a phase at the current block timestamp
+    fn get_phase_price_internal(&self, phase_id: PhaseId) -> Result<Balance, PSP34Error>;
+
+    /// Check if a phase can not mint anymore
+    fn is_phase_over(&self, phase_id: PhaseId) -> bool;
+
+    /// Get time a phase closes at, its end or the start of the next phase
+    fn get_phase_close_at(&self, phase_id: PhaseId) -> Option<u64>;
+
+    /// Record a payment in a rebate auction phase, `mint_value` excludes any premium
+    fn record_auction_sale(
+        &mut self,
+        account_id: AccountId,
+        minting_status: &MintingStatus,
+        mint_amount: u64,
+        mint_value: Balance,
+    ) -> Result<(), PSP34Error>;
+
+    fn get_rebate_amount_internal(&self, phase_id: PhaseId, account_id: AccountId) -> Balance;
+
+    /// Get sum of rebates still claimable in all rebate auction phases
+    fn get_outstanding_rebates_internal(&self) -> Balance;
+
+    /// Get number of tokens left to mint in a phase, including allocation rolled over
+    /// from earlier phases, `None` when the phase is not capped
+    fn get_phase_remaining_allocation_internal(&self, phase_id: PhaseId) -> Option<u64>;
+
+    /// Set the remaining slots of an account, listing the account in the allowlist of the phase
+    fn set_allowlist_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Add slots on top of the remaining slots of an account, listing it if needed
+    fn increase_allowlist_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Take slots off the remaining slots of an allowlisted account
+    fn decrease_allowlist_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Get owner of a partner collection token through a cross-contract call, `None` when the
+    /// token does not exist or the call fails
+    fn get_gated_token_owner(&self, collection: AccountId, id: Id) -> Option<AccountId>;
+
+    /// Use slots of the given partner tokens of an account to mint in an allowlist phase.
+    /// At most `MAX_GATED_TOKENS` tokens are checked, one ownership call each, and only until
+    /// enough slots are found.
+    fn claim_gated_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+        gated_tokens: &[(AccountId, Id)],
+    ) -> Result<(), PSP34Error>;
+
+    /// Take an account and its slots off the allowlist of a phase
+    fn remove_allowlist_account(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> Result<(), PSP34Error>;
+
+    /// Check that every token is owned by the account and listed only once
+    fn check_owned_tokens(&self, account_id: AccountId, ids: &Vec<Id>) -> Result<(), PSP34Error>;
+
+    fn _emit_schedule_changed_event(
+        &self,
+        phase_id: PhaseId,
+        old_schedule: Option<PhaseSchedule>,
+        new_schedule: Option<PhaseSchedule>,
+    );
+
+    fn _emit_minting_status_forced_event(&self, minting_status: Option<MintingStatus>);
+
+    fn _emit_voucher_signer_changed_event(&self, voucher_signer: Option<[u8; 33]>);
+
+    fn _emit_allowlist_updated_event(
+        &self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        old_amount: Option<u64>,
+        new_amount: Option<u64>,
+    );
+
+    fn _emit_redeemed_event(
+        &self,
+        account_id: AccountId,
+        tier: RedemptionTierId,
+        burned_ids: Vec<Id>,
+        id: Id,
+    );
 }
 
 impl<T> Launchpad for T
@@ -71,45 +309,89 @@ where
     T: Storage<Data>
         + Storage<psp34::Data<enumerable::Balances>>
         + Storage<ownable::Data>
+        + Storage<pausable::Data>
         + Storage<metadata::Data>
         + psp34::extensions::metadata::PSP34Metadata
-        + psp34::Internal,
+        + psp34::Internal
+        + pausable::Internal,
 {
     /// Mint one or more tokens
+    #[modifiers(when_not_paused)]
     default fn mint(&mut self, to: AccountId, mint_amount: u64) -> Result<(), PSP34Error> {
-        let caller_id = Self::env().caller();
-        let minting_status = self.get_current_minting_status();
-        let transferred_value = Self::env().transferred_value();
+        self.mint_paid(to, mint_amount, MintAuthorization::Allowlist)
+    }
 
-        self.check_amount(mint_amount)?;
-        self.check_value(transferred_value, mint_amount, &minting_status)?;
-        self.check_and_update_allowed_to_mint(caller_id, mint_amount, &minting_status)?;
+    /// Mint tokens in an allowlist phase, using the slots of the caller's partner tokens
+    /// for what its allowlist slots do not cover
+    #[modifiers(when_not_paused)]
+    default fn mint_gated(
+        &mut self,
+        to: AccountId,
+        mint_amount: u64,
+        gated_tokens: Vec<(AccountId, Id)>,
+    ) -> Result<(), PSP34Error> {
+        self.mint_paid(to, mint_amount, MintAuthorization::TokenGate(gated_tokens))
+    }
 
-        for _ in 0..mint_amount {
-            let mint_id = self.get_mint_id();
-            self.data::<psp34::Data<enumerable::Balances>>()
-                ._mint_to(to, Id::U64(mint_id))?;
-            self._emit_transfer_event(None, Some(to), Id::U64(mint_id));
-            self.data::<Data>()
-                .minting_type_for_token
-                .insert(mint_id, &minting_status.to_index());
+    /// Mint tokens within an allocation proven against the Merkle root of the current phase
+    #[modifiers(when_not_paused)]
+    default fn mint_allowlisted(
+        &mut self,
+        to: AccountId,
+        mint_amount: u64,
+        allocation: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+        let root = match self.get_current_minting_status() {
+            MintingStatus::Phase(phase_id) => self.data::<Data>().allowlist_roots.get(phase_id),
+            _ => None,
+        };
+        let is_valid = match root {
+            Some(root) => verify_proof(&root, allowlist_leaf(&caller_id, allocation), &proof),
+            None => false,
+        };
+        if !is_valid {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidAllowlistProof.as_str(),
+            )));
         }
+        self.mint_paid(to, mint_amount, MintAuthorization::MerkleProof(allocation))
+    }
 
-        self.data::<Data>().total_sales += transferred_value;
-        Ok(())
+    /// Mint tokens for the caller with a voucher of the voucher signer
+    #[modifiers(when_not_paused)]
+    default fn mint_with_voucher(
+        &mut self,
+        voucher: MintVoucher,
+        signature: [u8; 65],
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.use_voucher(&voucher, &signature, mint_amount)?;
+        self.mint_paid(
+            voucher.account,
+            mint_amount,
+            MintAuthorization::Voucher(voucher.price),
+        )
     }
 
     /// Mint next available token for the caller
+    #[modifiers(when_not_paused)]
     default fn mint_next(&mut self) -> Result<(), PSP34Error> {
         let caller_id = Self::env().caller();
         let minting_status = self.get_current_minting_status();
         let transferred_value = Self::env().transferred_value();
 
         self.check_amount(1)?;
-        self.check_value(transferred_value, 1, &minting_status)?;
-        self.check_and_update_allowed_to_mint(caller_id, 1, &minting_status)?;
-
-        let mint_id = self.get_mint_id();
+        let mint_value = self.check_value(transferred_value, 1, &minting_status)?;
+        self.check_and_update_allowed_to_mint(
+            caller_id,
+            1,
+            &minting_status,
+            &MintAuthorization::Allowlist,
+        )?;
+
+        let mint_id = self.get_mint_id()?;
         self.data::<psp34::Data<enumerable::Balances>>()
             ._mint_to(caller_id, Id::U64(mint_id))?;
 
@@ -118,378 +400,2599 @@ where
         self.data::<Data>()
             .minting_type_for_token
             .insert(mint_id, &minting_status.to_index());
+        self.record_token_sale(mint_id, caller_id, mint_value);
 
-        self.data::<Data>().total_sales += transferred_value;
+        self.record_payment(caller_id, 1, mint_value);
+        self.record_auction_sale(caller_id, &minting_status, 1, mint_value)?;
+        self.refund_overpayment(caller_id, transferred_value, mint_value)?;
         return Ok(());
     }
 
-    /// Withdraws funds to contract owner
-    default fn withdraw_launchpad(&mut self) -> Result<(), PSP34Error> {
+    /// Mint a token id chosen by the caller
+    #[modifiers(when_not_paused)]
+    default fn mint_id(&mut self, to: AccountId, id: TokenId) -> Result<(), PSP34Error> {
         let caller_id = Self::env().caller();
+        let minting_status = self.get_current_minting_status();
+        let transferred_value = Self::env().transferred_value();
 
-        if caller_id != self.data::<Data>().launchpad_treasury.unwrap() {
+        self.check_id_choosable()?;
+
+        self.check_amount(1)?;
+        let token_set_idx =
+            self.get_token_set_index(id)
+                .ok_or(PSP34Error::Custom(String::from(
+                    Shiden34Error::TokenNotAvailable.as_str(),
+                )))?;
+        self.bind_metadata_commitment()?;
+
+        let premium = self.data::<Data>().mint_id_premium;
+        if transferred_value < premium {
             return Err(PSP34Error::Custom(String::from(
-                Shiden34Error::Unauthorized.as_str(),
+                Shiden34Error::BadMintValue.as_str(),
             )));
         }
+        let mint_value =
+            self.check_value(transferred_value - premium, 1, &minting_status)? + premium;
+        self.check_and_update_allowed_to_mint(
+            caller_id,
+            1,
+            &minting_status,
+            &MintAuthorization::Allowlist,
+        )?;
+
+        self.remove_token_set_index(token_set_idx);
+        self.record_mint_entropy(id);
+        self.data::<psp34::Data<enumerable::Balances>>()
+            ._mint_to(to, Id::U64(id))?;
+        self._emit_transfer_event(None, Some(to), Id::U64(id));
+        self.data::<Data>()
+            .minting_type_for_token
+            .insert(id, &minting_status.to_index());
+        self.record_token_sale(id, caller_id, mint_value);
 
-        let available_to_withdraw = self.get_available_to_withdraw_launchpad_internal();
-
-        self.data::<Data>().withdrawn_sales_launchpad += available_to_withdraw;
+        self.record_payment(caller_id, 1, mint_value);
+        self.record_auction_sale(caller_id, &minting_status, 1, mint_value - premium)?;
+        self.refund_overpayment(caller_id, transferred_value, mint_value)?;
+        Ok(())
+    }
 
-        Self::env()
-            .transfer(caller_id, available_to_withdraw)
-            .map_err(|_| {
-                PSP34Error::Custom(String::from(Shiden34Error::WithdrawalFailed.as_str()))
-            })?;
-        return Ok(());
+    #[modifiers(only_owner)]
+    default fn create_token_auction(
+        &mut self,
+        id: TokenId,
+        reserve_price: Balance,
+        end_at: u64,
+    ) -> Result<(), PSP34Error> {
+        self.check_id_choosable()?;
+        if end_at <= Self::env().block_timestamp() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidAuction.as_str(),
+            )));
+        }
+        self.check_amount(1)?;
+        let token_set_idx =
+            self.get_token_set_index(id)
+                .ok_or(PSP34Error::Custom(String::from(
+                    Shiden34Error::TokenNotAvailable.as_str(),
+                )))?;
+        self.bind_metadata_commitment()?;
+
+        self.remove_token_set_index(token_set_idx);
+        self.data::<Data>().token_auctions.insert(
+            id,
+            &TokenAuction {
+                reserve_price,
+                end_at,
+                highest_bidder: None,
+                highest_bid: 0,
+            },
+        );
+        Ok(())
     }
 
-    default fn withdraw_project(&mut self) -> Result<(), PSP34Error> {
+    #[modifiers(when_not_paused)]
+    default fn bid(&mut self, id: TokenId) -> Result<(), PSP34Error> {
         let caller_id = Self::env().caller();
+        let transferred_value = Self::env().transferred_value();
+        let mut auction = self.get_token_auction_internal(id)?;
 
-        if caller_id != self.data::<Data>().project_treasury.unwrap() {
+        if Self::env().block_timestamp() > auction.end_at {
             return Err(PSP34Error::Custom(String::from(
-                Shiden34Error::Unauthorized.as_str(),
+                Shiden34Error::AuctionEnded.as_str(),
+            )));
+        }
+        if transferred_value < auction.reserve_price
+            || (auction.highest_bidder.is_some() && transferred_value <= auction.highest_bid)
+        {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::BidTooLow.as_str(),
             )));
         }
 
-        let available_to_withdraw = self.get_available_to_withdraw_project_internal();
+        // the outbid bidder pulls the bid back, so a bidder that can not receive it
+        // does not stop others from bidding
+        if let Some(bidder) = auction.highest_bidder {
+            let refund = self.get_bid_refund(bidder);
+            self.data::<Data>()
+                .bid_refunds
+                .insert(bidder, &(refund + auction.highest_bid));
+        }
+        auction.highest_bidder = Some(caller_id);
+        auction.highest_bid = transferred_value;
+        self.data::<Data>().token_auctions.insert(id, &auction);
+        Ok(())
+    }
 
-        self.data::<Data>().withdrawn_sales_project += available_to_withdraw;
+    default fn withdraw_bid_refund(&mut self) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+        let refund = self.get_bid_refund(caller_id);
+        if refund == 0 {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::NoBidRefund.as_str(),
+            )));
+        }
+        self.data::<Data>().bid_refunds.remove(caller_id);
 
         Self::env()
-            .transfer(caller_id, available_to_withdraw)
-            .map_err(|_| {
-                PSP34Error::Custom(String::from(Shiden34Error::WithdrawalFailed.as_str()))
-            })?;
-        return Ok(());
-    }
-
-    default fn get_available_to_withdraw_launchpad(&self) -> Balance {
-        self.get_available_to_withdraw_launchpad_internal()
+            .transfer(caller_id, refund)
+            .map_err(|_| PSP34Error::Custom(String::from(Shiden34Error::RefundFailed.as_str())))
     }
 
-    default fn get_available_to_withdraw_project(&self) -> Balance {
-        self.get_available_to_withdraw_project_internal()
+    default fn get_bid_refund(&self, account_id: AccountId) -> Balance {
+        self.data::<Data>().bid_refunds.get(account_id).unwrap_or(0)
     }
 
-    /// Set max number of tokens which could be minted per call
-    #[modifiers(only_owner)]
-    default fn set_max_mint_amount(&mut self, max_amount: u64) -> Result<(), PSP34Error> {
-        self.data::<Data>().max_amount = max_amount;
+    /// Mint an auctioned token to the highest bidder once the auction ended,
+    /// or put it back in the token set when nobody bid
+    #[modifiers(when_not_paused)]
+    default fn settle_token_auction(&mut self, id: TokenId) -> Result<(), PSP34Error> {
+        let auction = self.get_token_auction_internal(id)?;
+        if Self::env().block_timestamp() <= auction.end_at {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::AuctionNotEnded.as_str(),
+            )));
+        }
 
+        self.data::<Data>().token_auctions.remove(id);
+        match auction.highest_bidder {
+            Some(winner) => {
+                self.record_mint_entropy(id);
+                self.data::<psp34::Data<enumerable::Balances>>()
+                    ._mint_to(winner, Id::U64(id))?;
+                self._emit_transfer_event(None, Some(winner), Id::U64(id));
+                self.record_token_sale(id, winner, auction.highest_bid);
+                self.record_payment(winner, 1, auction.highest_bid);
+            }
+            None => self.add_token_set_value(id),
+        }
         Ok(())
     }
 
-    default fn max_supply(&self) -> u64 {
-        self.data::<Data>().max_supply
-    }
-
-    /// Get token price
-    default fn price(&self) -> Balance {
-        self.data::<Data>().price_per_mint
-    }
-
-    /// Get max number of tokens which could be minted per call
-    default fn get_max_mint_amount(&mut self) -> u64 {
-        self.data::<Data>().max_amount
+    default fn get_token_auction(&self, id: TokenId) -> Option<TokenAuction> {
+        self.data::<Data>().token_auctions.get(id)
     }
 
-    default fn mint_project(&mut self, to: AccountId, mint_amount: u64) -> Result<(), PSP34Error> {
+    /// Enter the running raffle phase with a deposit of its price
+    #[modifiers(when_not_paused)]
+    default fn enter_raffle(&mut self) -> Result<(), PSP34Error> {
         let caller_id = Self::env().caller();
+        let phase_id = match self.get_current_minting_status() {
+            MintingStatus::Phase(phase_id) => phase_id,
+            _ => {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::UnableToMint.as_str(),
+                )))
+            }
+        };
+        self.check_raffle_phase(phase_id)?;
 
-        if caller_id != self.data::<Data>().project_treasury.unwrap() {
+        if self.get_phase_price_internal(phase_id)? != Self::env().transferred_value() {
             return Err(PSP34Error::Custom(String::from(
-                Shiden34Error::Unauthorized.as_str(),
+                Shiden34Error::BadMintValue.as_str(),
             )));
         }
-
-        for _ in 0..mint_amount {
-            let mint_id = self.get_mint_id();
-            self.data::<psp34::Data<enumerable::Balances>>()
-                ._mint_to(to, Id::U64(mint_id))?;
-            self._emit_transfer_event(None, Some(to), Id::U64(mint_id));
+        if self
+            .data::<Data>()
+            .raffle_deposits
+            .contains((phase_id, caller_id))
+        {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::AlreadyEnteredRaffle.as_str(),
+            )));
+        }
+        if !self.data::<Data>().raffle_commitments.contains(phase_id) {
+            let commitment =
+                self.data::<Data>()
+                    .randomness_commitment
+                    .ok_or(PSP34Error::Custom(String::from(
+                        Shiden34Error::RandomnessNotCommitted.as_str(),
+                    )))?;
+            self.data::<Data>()
+                .raffle_commitments
+                .insert(phase_id, &commitment);
+            self.data::<Data>().randomness_commitment = None;
         }
 
-        Ok(())
-    }
-
-    #[modifiers(only_owner)]
-    default fn add_account_to_prepresale(
-        &mut self,
-        account_id: AccountId,
-        mint_amount: u64,
-    ) -> Result<(), PSP34Error> {
+        let entry_count = self
+            .data::<Data>()
+            .raffle_entry_count
+            .get(phase_id)
+            .unwrap_or(0);
+        self.data::<Data>()
+            .raffle_entries
+            .insert((phase_id, entry_count), &caller_id);
+        self.data::<Data>()
+            .raffle_entry_count
+            .insert(phase_id, &(entry_count + 1));
         self.data::<Data>()
-            .prepresale_whitelisted
-            .insert(account_id, &mint_amount);
+            .raffle_deposits
+            .insert((phase_id, caller_id), &Self::env().transferred_value());
         Ok(())
     }
 
-    #[modifiers(only_owner)]
-    fn add_account_to_prepresale_batch(
+    /// Draw up to `max_winners` winners of a raffle phase once its seed is revealed and mint them
+    /// a token each. The first call fixes the number of winners, the next ones continue
+    /// a Fisher-Yates shuffle of the entries by the seed, so the draw can be replayed.
+    #[modifiers(when_not_paused)]
+    default fn draw_raffle(
         &mut self,
-        account_id_mint_amounts: Vec<(AccountId, u64)>,
+        phase_id: PhaseId,
+        max_winners: u64,
     ) -> Result<(), PSP34Error> {
-        for (account_id, mint_amount) in account_id_mint_amounts {
+        self.check_raffle_phase(phase_id)?;
+        if !self.is_phase_over(phase_id) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::UnableToMint.as_str(),
+            )));
+        }
+        if self.is_raffle_drawn(phase_id) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::RaffleAlreadyDrawn.as_str(),
+            )));
+        }
+        if self.is_raffle_draw_expired(phase_id) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::RaffleDrawExpired.as_str(),
+            )));
+        }
+        let seed = self
+            .data::<Data>()
+            .raffle_seeds
+            .get(phase_id)
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::RandomnessNotRevealed.as_str(),
+            )))?;
+
+        let entry_count = self
+            .data::<Data>()
+            .raffle_entry_count
+            .get(phase_id)
+            .unwrap_or(0);
+        if !self.data::<Data>().raffle_winner_count.contains(phase_id) {
+            // winner tokens are taken off the sale supply and the phase allocation right away,
+            // so that later batches can always mint them
+            let mut winner_count = entry_count.min(self.get_sale_supply_remaining_internal());
+            if let Some(remaining_allocation) =
+                self.get_phase_remaining_allocation_internal(phase_id)
+            {
+                winner_count = winner_count.min(remaining_allocation);
+            }
             self.data::<Data>()
-                .prepresale_whitelisted
-                .insert(account_id, &mint_amount);
+                .raffle_winner_count
+                .insert(phase_id, &winner_count);
+            self.data::<Data>().raffle_undrawn_supply += winner_count;
+            let phase_minted = self.data::<Data>().phase_minted.get(phase_id).unwrap_or(0);
+            self.data::<Data>()
+                .phase_minted
+                .insert(phase_id, &(phase_minted + winner_count));
         }
-        Ok(())
-    }
 
-    #[modifiers(only_owner)]
-    fn add_account_to_presale_batch(
-        &mut self,
+        let winner_count = self
+            .data::<Data>()
+            .raffle_winner_count
+            .get(phase_id)
+            .unwrap_or(0);
+        let drawn_count = self
+            .data::<Data>()
+            .raffle_drawn_count
+            .get(phase_id)
+            .unwrap_or(0);
+        let end = drawn_count.saturating_add(max_winners).min(winner_count);
+
+        let minting_type = MintingStatus::Phase(phase_id).to_index();
+        for position in drawn_count..end {
+            let max_offset = entry_count - 1 - position;
+            let swap_position = position
+                + DeterministicRandomness::with_counter(seed, position).next_u64(max_offset);
+            let entry_index = self.get_raffle_shuffled_index(phase_id, swap_position);
+            if swap_position != position {
+                let position_index = self.get_raffle_shuffled_index(phase_id, position);
+                self.data::<Data>()
+                    .raffle_shuffle
+                    .insert((phase_id, swap_position), &position_index);
+                self.data::<Data>()
+                    .raffle_shuffle
+                    .insert((phase_id, position), &entry_index);
+            }
+            let winner = self
+                .data::<Data>()
+                .raffle_entries
+                .get((phase_id, entry_index))
+                .unwrap();
+
+            self.data::<Data>().raffle_undrawn_supply -= 1;
+            let mint_id = self.get_mint_id()?;
+            self.data::<psp34::Data<enumerable::Balances>>()
+                ._mint_to(winner, Id::U64(mint_id))?;
+            self._emit_transfer_event(None, Some(winner), Id::U64(mint_id));
+            self.data::<Data>()
+                .minting_type_for_token
+                .insert(mint_id, &minting_type);
+
+            let deposit = self
+                .data::<Data>()
+                .raffle_deposits
+                .get((phase_id, winner))
+                .unwrap_or(0);
+            self.data::<Data>()
+                .raffle_deposits
+                .insert((phase_id, winner), &0);
+            self.record_token_sale(mint_id, winner, deposit);
+            self.record_payment(winner, 1, deposit);
+            self.data::<Data>()
+                .phase_account_minted
+                .insert((phase_id, winner), &1);
+        }
+        self.data::<Data>()
+            .raffle_drawn_count
+            .insert(phase_id, &end);
+        Ok(())
+    }
+
+    /// Refund the deposit of a raffle entry which was not drawn, or of any entry left undrawn
+    /// once the draw timed out
+    default fn claim_raffle_refund(&mut self, phase_id: PhaseId) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+        if !self.is_raffle_drawn(phase_id) {
+            if !self.is_raffle_draw_expired(phase_id) {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::RaffleNotDrawn.as_str(),
+                )));
+            }
+            self.close_raffle_draw(phase_id);
+        }
+
+        let deposit = self
+            .data::<Data>()
+            .raffle_deposits
+            .get((phase_id, caller_id))
+            .unwrap_or(0);
+        if deposit == 0 {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::NoRaffleRefund.as_str(),
+            )));
+        }
+        self.data::<Data>()
+            .raffle_deposits
+            .insert((phase_id, caller_id), &0);
+
+        Self::env()
+            .transfer(caller_id, deposit)
+            .map_err(|_| PSP34Error::Custom(String::from(Shiden34Error::RefundFailed.as_str())))
+    }
+
+    default fn get_raffle_entries(
+        &self,
+        phase_id: PhaseId,
+        offset: u64,
+        limit: u64,
+    ) -> Vec<AccountId> {
+        let entry_count = self.get_raffle_entry_count(phase_id);
+        let end = offset.saturating_add(limit).min(entry_count);
+        (offset..end)
+            .filter_map(|index| self.data::<Data>().raffle_entries.get((phase_id, index)))
+            .collect()
+    }
+
+    default fn get_raffle_entry_count(&self, phase_id: PhaseId) -> u64 {
+        self.data::<Data>()
+            .raffle_entry_count
+            .get(phase_id)
+            .unwrap_or(0)
+    }
+
+    default fn get_raffle_deposit(&self, phase_id: PhaseId, account_id: AccountId) -> Balance {
+        self.data::<Data>()
+            .raffle_deposits
+            .get((phase_id, account_id))
+            .unwrap_or(0)
+    }
+
+    default fn get_raffle_seed(&self, phase_id: PhaseId) -> Option<[u8; 32]> {
+        self.data::<Data>().raffle_seeds.get(phase_id)
+    }
+
+    default fn get_raffle_commitment(&self, phase_id: PhaseId) -> Option<[u8; 32]> {
+        self.data::<Data>().raffle_commitments.get(phase_id)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_raffle_draw_timeout(&mut self, timeout: u64) -> Result<(), PSP34Error> {
+        if self.has_sale_started() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::SaleAlreadyStarted.as_str(),
+            )));
+        }
+        self.data::<Data>().raffle_draw_timeout = timeout;
+        Ok(())
+    }
+
+    default fn get_raffle_draw_timeout(&self) -> u64 {
+        self.data::<Data>().raffle_draw_timeout
+    }
+
+    default fn get_raffle_winners(
+        &self,
+        phase_id: PhaseId,
+        offset: u64,
+        limit: u64,
+    ) -> Vec<AccountId> {
+        let drawn_count = self
+            .data::<Data>()
+            .raffle_drawn_count
+            .get(phase_id)
+            .unwrap_or(0);
+        let end = offset.saturating_add(limit).min(drawn_count);
+        (offset..end)
+            .filter_map(|position| {
+                let entry_index = self.get_raffle_shuffled_index(phase_id, position);
+                self.data::<Data>()
+                    .raffle_entries
+                    .get((phase_id, entry_index))
+            })
+            .collect()
+    }
+
+    default fn get_raffle_winner_count(&self, phase_id: PhaseId) -> Option<u64> {
+        self.data::<Data>().raffle_winner_count.get(phase_id)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_min_sales(&mut self, min_sales: Option<Balance>) -> Result<(), PSP34Error> {
+        if self.has_sale_started() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::SaleAlreadyStarted.as_str(),
+            )));
+        }
+        self.data::<Data>().min_sales = min_sales;
+        Ok(())
+    }
+
+    default fn get_min_sales(&self) -> Option<Balance> {
+        self.data::<Data>().min_sales
+    }
+
+    default fn is_refundable(&self) -> bool {
+        self.is_sale_failed()
+    }
+
+    /// Burn tokens the caller bought in the sale and refund the price paid for each of them
+    default fn claim_refund(&mut self, ids: Vec<Id>) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+        if !self.is_sale_failed() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::SaleNotFailed.as_str(),
+            )));
+        }
+        if ids.is_empty() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::BadRefundAmount.as_str(),
+            )));
+        }
+        self.check_owned_tokens(caller_id, &ids)?;
+
+        // only the buyer gets a token refunded, airdropped, project, redeemed or
+        // resold tokens were not paid to the contract by the caller
+        let mut refunded: Balance = 0;
+        let mut token_ids = Vec::new();
+        for id in ids.iter() {
+            let token_id = match id {
+                Id::U64(token_id) => *token_id,
+                _ => {
+                    return Err(PSP34Error::Custom(String::from(
+                        Shiden34Error::NotRefundable.as_str(),
+                    )))
+                }
+            };
+            match self.data::<Data>().token_sales.get(token_id) {
+                Some((buyer, _)) if buyer == caller_id => {}
+                _ => {
+                    return Err(PSP34Error::Custom(String::from(
+                        Shiden34Error::NotRefundable.as_str(),
+                    )))
+                }
+            }
+            refunded += self.get_refundable_price(token_id)?;
+            token_ids.push(token_id);
+        }
+
+        for token_id in token_ids.iter() {
+            self.data::<psp34::Data<enumerable::Balances>>()
+                ._burn_from(caller_id, Id::U64(*token_id))?;
+            self._emit_transfer_event(Some(caller_id), None, Id::U64(*token_id));
+            self.data::<Data>().token_sales.remove(token_id);
+        }
+
+        let paid = self.data::<Data>().account_paid.get(caller_id).unwrap_or(0);
+        self.data::<Data>()
+            .account_paid
+            .insert(caller_id, &paid.saturating_sub(refunded));
+        let bought = self
+            .data::<Data>()
+            .account_bought
+            .get(caller_id)
+            .unwrap_or(0);
+        self.data::<Data>()
+            .account_bought
+            .insert(caller_id, &bought.saturating_sub(token_ids.len() as u64));
+        self.data::<Data>().total_sales -= refunded;
+
+        Self::env()
+            .transfer(caller_id, refunded)
+            .map_err(|_| PSP34Error::Custom(String::from(Shiden34Error::RefundFailed.as_str())))
+    }
+
+    default fn get_account_paid_amount(&self, account_id: AccountId) -> Balance {
+        self.data::<Data>()
+            .account_paid
+            .get(account_id)
+            .unwrap_or(0)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_mint_id_premium(&mut self, premium: Balance) -> Result<(), PSP34Error> {
+        self.data::<Data>().mint_id_premium = premium;
+        Ok(())
+    }
+
+    default fn get_mint_id_premium(&self) -> Balance {
+        self.data::<Data>().mint_id_premium
+    }
+
+    default fn is_available_to_mint(&self, id: TokenId) -> bool {
+        self.get_token_set_index(id).is_some()
+    }
+
+    /// Withdraws funds to contract owner
+    #[modifiers(when_not_paused)]
+    default fn withdraw_launchpad(&mut self) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+
+        if caller_id != self.data::<Data>().launchpad_treasury.unwrap() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::Unauthorized.as_str(),
+            )));
+        }
+
+        if self.is_sale_failed() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::SaleFailed.as_str(),
+            )));
+        }
+
+        let available_to_withdraw = self.get_available_to_withdraw_launchpad_internal();
+
+        self.data::<Data>().withdrawn_sales_launchpad += available_to_withdraw;
+
+        Self::env()
+            .transfer(caller_id, available_to_withdraw)
+            .map_err(|_| {
+                PSP34Error::Custom(String::from(Shiden34Error::WithdrawalFailed.as_str()))
+            })?;
+        return Ok(());
+    }
+
+    #[modifiers(when_not_paused)]
+    default fn withdraw_project(&mut self) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+
+        if caller_id != self.data::<Data>().project_treasury.unwrap() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::Unauthorized.as_str(),
+            )));
+        }
+
+        if self.is_sale_failed() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::SaleFailed.as_str(),
+            )));
+        }
+
+        let available_to_withdraw = self.get_available_to_withdraw_project_internal();
+
+        self.data::<Data>().withdrawn_sales_project += available_to_withdraw;
+
+        Self::env()
+            .transfer(caller_id, available_to_withdraw)
+            .map_err(|_| {
+                PSP34Error::Custom(String::from(Shiden34Error::WithdrawalFailed.as_str()))
+            })?;
+        return Ok(());
+    }
+
+    #[modifiers(when_not_paused)]
+    default fn burn(&mut self, id: Id) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+        self.check_owned_tokens(caller_id, &Vec::from([id.clone()]))?;
+
+        self.data::<psp34::Data<enumerable::Balances>>()
+            ._burn_from(caller_id, id.clone())?;
+        self.remove_token_sale(&id);
+        self._emit_transfer_event(Some(caller_id), None, id);
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn add_redemption_tier(
+        &mut self,
+        burn_amount: u64,
+        max_supply: u64,
+    ) -> Result<(), PSP34Error> {
+        if burn_amount == 0 || self.data::<Data>().redemption_tiers.len() >= 127 {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidRedemptionTier.as_str(),
+            )));
+        }
+
+        let first_token_id = match self.data::<Data>().redemption_tiers.last() {
+            Some(tier) => tier.first_token_id + tier.max_supply,
+            None => self.data::<Data>().max_supply + 1,
+        };
+        self.data::<Data>().redemption_tiers.push(RedemptionTier {
+            burn_amount,
+            first_token_id,
+            max_supply,
+            minted: 0,
+        });
+        Ok(())
+    }
+
+    default fn get_redemption_tiers(&self) -> Vec<RedemptionTier> {
+        self.data::<Data>().redemption_tiers.clone()
+    }
+
+    #[modifiers(when_not_paused)]
+    default fn redeem(&mut self, tier: RedemptionTierId, ids: Vec<Id>) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+        let redemption_tier = self
+            .data::<Data>()
+            .redemption_tiers
+            .get(tier as usize)
+            .cloned()
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidRedemptionTier.as_str(),
+            )))?;
+
+        if ids.len() as u64 != redemption_tier.burn_amount {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::BadRedemptionAmount.as_str(),
+            )));
+        }
+        if redemption_tier.minted >= redemption_tier.max_supply {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::RedemptionTierFull.as_str(),
+            )));
+        }
+        self.check_owned_tokens(caller_id, &ids)?;
+
+        for id in ids.iter() {
+            self.data::<psp34::Data<enumerable::Balances>>()
+                ._burn_from(caller_id, id.clone())?;
+            self.remove_token_sale(id);
+            self._emit_transfer_event(Some(caller_id), None, id.clone());
+        }
+
+        // the redeemed token is not a sale token, it has no price to refund and
+        // does not count in the allocation of any phase
+        let mint_id = redemption_tier.first_token_id + redemption_tier.minted;
+        self.data::<psp34::Data<enumerable::Balances>>()
+            ._mint_to(caller_id, Id::U64(mint_id))?;
+        self._emit_transfer_event(None, Some(caller_id), Id::U64(mint_id));
+        self.data::<Data>()
+            .minting_type_for_token
+            .insert(mint_id, &(REDEEMED_MINTING_TYPE + tier));
+        self.data::<Data>().redemption_tiers[tier as usize].minted += 1;
+
+        self._emit_redeemed_event(caller_id, tier, ids, Id::U64(mint_id));
+        Ok(())
+    }
+
+    default fn get_available_to_withdraw_launchpad(&self) -> Balance {
+        self.get_available_to_withdraw_launchpad_internal()
+    }
+
+    default fn get_available_to_withdraw_project(&self) -> Balance {
+        self.get_available_to_withdraw_project_internal()
+    }
+
+    /// Set max number of tokens which could be minted per call
+    #[modifiers(only_owner)]
+    default fn set_max_mint_amount(&mut self, max_amount: u64) -> Result<(), PSP34Error> {
+        self.data::<Data>().max_amount = max_amount;
+
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_accept_overpayment(
+        &mut self,
+        accept_overpayment: bool,
+    ) -> Result<(), PSP34Error> {
+        self.data::<Data>().accept_overpayment = accept_overpayment;
+        Ok(())
+    }
+
+    default fn get_accept_overpayment(&self) -> bool {
+        self.data::<Data>().accept_overpayment
+    }
+
+    default fn max_supply(&self) -> u64 {
+        self.data::<Data>().max_supply
+    }
+
+    default fn get_reserve_remaining(&self) -> u64 {
+        self.get_reserve_remaining_internal()
+    }
+
+    default fn get_sale_supply_remaining(&self) -> u64 {
+        self.get_sale_supply_remaining_internal()
+    }
+
+    default fn get_id_assignment(&self) -> IdAssignment {
+        self.data::<Data>().id_assignment
+    }
+
+    default fn get_starting_index(&self) -> Option<u64> {
+        self.data::<Data>().starting_index
+    }
+
+    default fn get_metadata_id(&self, token_id: TokenId) -> Option<u64> {
+        self.get_metadata_id_internal(token_id)
+    }
+
+    /// Get token price of the last phase
+    default fn price(&self) -> Balance {
+        self.get_last_phase_id()
+            .and_then(|phase_id| self.get_phase_price_internal(phase_id))
+            .unwrap_or_default()
+    }
+
+    /// Get max number of tokens which could be minted per call
+    default fn get_max_mint_amount(&mut self) -> u64 {
+        self.data::<Data>().max_amount
+    }
+
+    #[modifiers(when_not_paused)]
+    default fn mint_project(&mut self, to: AccountId, mint_amount: u64) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+
+        if caller_id != self.data::<Data>().project_treasury.unwrap() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::Unauthorized.as_str(),
+            )));
+        }
+
+        if mint_amount > self.get_reserve_remaining_internal() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::ReserveExhausted.as_str(),
+            )));
+        }
+
+        for _ in 0..mint_amount {
+            let mint_id = self.get_mint_id()?;
+            self.data::<psp34::Data<enumerable::Balances>>()
+                ._mint_to(to, Id::U64(mint_id))?;
+            self._emit_transfer_event(None, Some(to), Id::U64(mint_id));
+        }
+
+        self.data::<Data>().reserved_minted += mint_amount;
+        Ok(())
+    }
+
+    #[modifiers(when_not_paused)]
+    default fn airdrop(
+        &mut self,
+        account_id_mint_amounts: Vec<(AccountId, u64)>,
+    ) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+
+        if ![
+            self.data::<Data>().project_treasury.unwrap(),
+            self.data::<ownable::Data>().owner,
+        ]
+        .contains(&caller_id)
+        {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::Unauthorized.as_str(),
+            )));
+        }
+
+        // check the whole batch upfront so the airdrop never stops halfway
+        let mut total_amount: u64 = 0;
+        for (_, mint_amount) in account_id_mint_amounts.iter() {
+            total_amount = total_amount
+                .checked_add(*mint_amount)
+                .ok_or(PSP34Error::Custom(String::from(
+                    Shiden34Error::CollectionIsFull.as_str(),
+                )))?;
+        }
+
+        let airdrop_from_reserve = self.data::<Data>().airdrop_from_reserve;
+        if airdrop_from_reserve {
+            if total_amount > self.get_reserve_remaining_internal() {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::ReserveExhausted.as_str(),
+                )));
+            }
+        } else if total_amount > self.get_sale_supply_remaining_internal() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::CollectionIsFull.as_str(),
+            )));
+        }
+
+        for (to, mint_amount) in account_id_mint_amounts {
+            for _ in 0..mint_amount {
+                let mint_id = self.get_mint_id()?;
+                self.data::<psp34::Data<enumerable::Balances>>()
+                    ._mint_to(to, Id::U64(mint_id))?;
+                self._emit_transfer_event(None, Some(to), Id::U64(mint_id));
+            }
+        }
+
+        if airdrop_from_reserve {
+            self.data::<Data>().reserved_minted += total_amount;
+        }
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_airdrop_from_reserve(
+        &mut self,
+        airdrop_from_reserve: bool,
+    ) -> Result<(), PSP34Error> {
+        self.data::<Data>().airdrop_from_reserve = airdrop_from_reserve;
+        Ok(())
+    }
+
+    default fn get_airdrop_from_reserve(&self) -> bool {
+        self.data::<Data>().airdrop_from_reserve
+    }
+
+    #[modifiers(only_owner)]
+    default fn add_account_to_allowlist(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.increase_allowlist_slots(phase_id, account_id, mint_amount)
+    }
+
+    #[modifiers(only_owner)]
+    default fn add_account_to_allowlist_batch(
+        &mut self,
+        phase_id: PhaseId,
+        account_id_mint_amounts: Vec<(AccountId, u64)>,
+    ) -> Result<(), PSP34Error> {
+        for (account_id, mint_amount) in account_id_mint_amounts {
+            self.increase_allowlist_slots(phase_id, account_id, mint_amount)?;
+        }
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_account_allowlist_minting_amount(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.set_allowlist_slots(phase_id, account_id, mint_amount)
+    }
+
+    #[modifiers(only_owner)]
+    default fn increase_account_allowlist_minting_amount(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.increase_allowlist_slots(phase_id, account_id, mint_amount)
+    }
+
+    #[modifiers(only_owner)]
+    default fn decrease_account_allowlist_minting_amount(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.decrease_allowlist_slots(phase_id, account_id, mint_amount)
+    }
+
+    #[modifiers(only_owner)]
+    default fn remove_account_from_allowlist(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> Result<(), PSP34Error> {
+        self.remove_allowlist_account(phase_id, account_id)
+    }
+
+    #[modifiers(only_owner)]
+    default fn remove_account_from_allowlist_batch(
+        &mut self,
+        phase_id: PhaseId,
+        account_ids: Vec<AccountId>,
+    ) -> Result<(), PSP34Error> {
+        for account_id in account_ids {
+            self.remove_allowlist_account(phase_id, account_id)?;
+        }
+        Ok(())
+    }
+
+    default fn get_allowlist(
+        &self,
+        phase_id: PhaseId,
+        offset: u64,
+        limit: u64,
+    ) -> Vec<(AccountId, u64)> {
+        let length = self
+            .data::<Data>()
+            .allowlist_length
+            .get(phase_id)
+            .unwrap_or(0);
+        let end = offset.saturating_add(limit).min(length);
+        (offset..end)
+            .filter_map(|position| {
+                let account_id = self
+                    .data::<Data>()
+                    .allowlist_accounts
+                    .get((phase_id, position))?;
+                let mint_amount = self
+                    .data::<Data>()
+                    .allowlist
+                    .get((phase_id, account_id))
+                    .unwrap_or(0);
+                Some((account_id, mint_amount))
+            })
+            .collect()
+    }
+
+    default fn get_allowlist_count(&self, phase_id: PhaseId) -> u64 {
+        self.data::<Data>()
+            .allowlist_length
+            .get(phase_id)
+            .unwrap_or(0)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_token_gates(
+        &mut self,
+        phase_id: PhaseId,
+        token_gates: Vec<TokenGate>,
+    ) -> Result<(), PSP34Error> {
+        self.get_phase(phase_id)?;
+        if token_gates.iter().any(|gate| gate.slots_per_token == 0) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidTokenGate.as_str(),
+            )));
+        }
+        if token_gates.is_empty() {
+            self.data::<Data>().token_gates.remove(phase_id);
+        } else {
+            self.data::<Data>()
+                .token_gates
+                .insert(phase_id, &token_gates);
+        }
+        Ok(())
+    }
+
+    default fn get_token_gates(&self, phase_id: PhaseId) -> Vec<TokenGate> {
+        self.data::<Data>()
+            .token_gates
+            .get(phase_id)
+            .unwrap_or_default()
+    }
+
+    default fn get_gated_token_claimed_amount(
+        &self,
+        phase_id: PhaseId,
+        collection: AccountId,
+        id: Id,
+    ) -> u64 {
+        self.data::<Data>()
+            .gated_token_claimed
+            .get((phase_id, collection, id))
+            .unwrap_or(0)
+    }
+
+    default fn get_account_allowlist_minting_amount(
+        &self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> Option<u64> {
+        self.data::<Data>().allowlist.get((phase_id, account_id))
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_voucher_signer(
+        &mut self,
+        voucher_signer: Option<[u8; 33]>,
+    ) -> Result<(), PSP34Error> {
+        self.data::<Data>().voucher_signer = voucher_signer;
+        self._emit_voucher_signer_changed_event(voucher_signer);
+        Ok(())
+    }
+
+    default fn get_voucher_signer(&self) -> Option<[u8; 33]> {
+        self.data::<Data>().voucher_signer
+    }
+
+    default fn is_voucher_nonce_used(&self, nonce: u64) -> bool {
+        self.data::<Data>().used_voucher_nonces.contains(nonce)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_allowlist_root(
+        &mut self,
+        phase_id: PhaseId,
+        root: Option<[u8; 32]>,
+    ) -> Result<(), PSP34Error> {
+        self.get_phase(phase_id)?;
+        match root {
+            Some(root) => self.data::<Data>().allowlist_roots.insert(phase_id, &root),
+            None => self.data::<Data>().allowlist_roots.remove(phase_id),
+        };
+        Ok(())
+    }
+
+    default fn get_allowlist_root(&self, phase_id: PhaseId) -> Option<[u8; 32]> {
+        self.data::<Data>().allowlist_roots.get(phase_id)
+    }
+
+    default fn get_account_allowlist_claimed_amount(
+        &self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> u64 {
+        self.data::<Data>()
+            .allowlist_claimed
+            .get((phase_id, account_id))
+            .unwrap_or(0)
+    }
+
+    #[modifiers(only_owner)]
+    default fn add_account_to_prepresale(
+        &mut self,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.increase_allowlist_slots(PREPRESALE_PHASE, account_id, mint_amount)
+    }
+
+    #[modifiers(only_owner)]
+    fn add_account_to_prepresale_batch(
+        &mut self,
+        account_id_mint_amounts: Vec<(AccountId, u64)>,
+    ) -> Result<(), PSP34Error> {
+        for (account_id, mint_amount) in account_id_mint_amounts {
+            self.increase_allowlist_slots(PREPRESALE_PHASE, account_id, mint_amount)?;
+        }
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    fn add_account_to_presale_batch(
+        &mut self,
         account_id_mint_amounts: Vec<(AccountId, u64)>,
     ) -> Result<(), PSP34Error> {
-        for (account_id, mint_amount) in account_id_mint_amounts {
+        for (account_id, mint_amount) in account_id_mint_amounts {
+            self.increase_allowlist_slots(PRESALE_PHASE, account_id, mint_amount)?;
+        }
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn add_account_to_presale(
+        &mut self,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.increase_allowlist_slots(PRESALE_PHASE, account_id, mint_amount)
+    }
+
+    /// Halt mints, burns, withdrawals and token transfers
+    #[modifiers(only_owner)]
+    default fn pause(&mut self) -> Result<(), PSP34Error> {
+        self._pause()
+    }
+
+    #[modifiers(only_owner)]
+    default fn unpause(&mut self) -> Result<(), PSP34Error> {
+        self._unpause()
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_minting_status(
+        &mut self,
+        minting_status: Option<MintingStatus>,
+    ) -> Result<(), PSP34Error> {
+        if let Some(MintingStatus::Phase(phase_id)) = minting_status {
+            self.get_phase(phase_id)?;
+        }
+        self.data::<Data>().forced_minting_status = minting_status;
+        self._emit_minting_status_forced_event(minting_status);
+        return Ok(());
+    }
+
+    default fn get_forced_minting_status(&self) -> Option<MintingStatus> {
+        self.data::<Data>().forced_minting_status
+    }
+
+    #[modifiers(only_owner)]
+    default fn commit_randomness(&mut self, commitment: [u8; 32]) -> Result<(), PSP34Error> {
+        if self.data::<Data>().randomness_commitment.is_some() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::RandomnessCommitmentPending.as_str(),
+            )));
+        }
+        self.data::<Data>().randomness_commitment = Some(commitment);
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn reveal_randomness(&mut self, secret: Vec<u8>) -> Result<(), PSP34Error> {
+        let commitment = Some(keccak256(&secret));
+        if self.data::<Data>().metadata_seed.is_none()
+            && self.data::<Data>().metadata_commitment == commitment
+        {
+            return self.reveal_metadata_seed(secret);
+        }
+
+        let phase_count = self.data::<Data>().phases.len() as PhaseId;
+        let phase_id = (0..phase_count)
+            .find(|&phase_id| {
+                self.data::<Data>().raffle_commitments.get(phase_id) == commitment
+                    && !self.data::<Data>().raffle_seeds.contains(phase_id)
+            })
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidRandomnessReveal.as_str(),
+            )))?;
+        if !self.is_phase_over(phase_id) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::RandomnessRevealTooEarly.as_str(),
+            )));
+        }
+
+        let mut input: Vec<u8> = Vec::new();
+        input.extend_from_slice(&secret);
+        input.push(phase_id);
+        self.data::<Data>()
+            .raffle_seeds
+            .insert(phase_id, &keccak256(&input));
+        Ok(())
+    }
+
+    default fn get_randomness_commitment(&self) -> Option<[u8; 32]> {
+        self.data::<Data>().randomness_commitment
+    }
+
+    default fn get_metadata_commitment(&self) -> Option<[u8; 32]> {
+        self.data::<Data>().metadata_commitment
+    }
+
+    default fn get_minting_status(&self) -> MintingStatus {
+        self.get_current_minting_status()
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_phases(&mut self, phases: Vec<Phase>) -> Result<(), PSP34Error> {
+        self.update_phases(phases)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_prepresale_start_at(&mut self, start_at: u64) -> Result<(), PSP34Error> {
+        let end_at = self.get_phase(PREPRESALE_PHASE)?.end_at;
+        self.set_phase_schedule(PREPRESALE_PHASE, start_at, end_at)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_presale_start_at(&mut self, start_at: u64) -> Result<(), PSP34Error> {
+        let end_at = self.get_phase(PRESALE_PHASE)?.end_at;
+        self.set_phase_schedule(PRESALE_PHASE, start_at, end_at)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_public_sale_start_at(&mut self, start_at: u64) -> Result<(), PSP34Error> {
+        let phase_id = self.get_last_phase_id()?;
+        let end_at = self.get_phase(phase_id)?.end_at;
+        self.set_phase_schedule(phase_id, start_at, end_at)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_public_sale_end_at(&mut self, end_at: Option<u64>) -> Result<(), PSP34Error> {
+        let phase_id = self.get_last_phase_id()?;
+        let start_at = self.get_phase(phase_id)?.start_at;
+        self.set_phase_schedule(phase_id, start_at, end_at)
+    }
+
+    default fn get_phases(&self) -> Vec<Phase> {
+        self.data::<Data>().phases.clone()
+    }
+
+    default fn get_current_phase(&self) -> Option<PhaseId> {
+        match self.get_current_minting_status() {
+            MintingStatus::Phase(phase_id) => Some(phase_id),
+            _ => None,
+        }
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_phase_allocation(
+        &mut self,
+        phase_id: PhaseId,
+        max_supply: Option<u64>,
+    ) -> Result<(), PSP34Error> {
+        self.get_phase(phase_id)?;
+        self.data::<Data>().phases[phase_id as usize].max_supply = max_supply;
+        Ok(())
+    }
+
+    default fn get_phase_remaining_allocation(&self, phase_id: PhaseId) -> Option<u64> {
+        self.get_phase_remaining_allocation_internal(phase_id)
+    }
+
+    default fn get_phase_minted_amount(&self, phase_id: PhaseId) -> u64 {
+        self.data::<Data>().phase_minted.get(phase_id).unwrap_or(0)
+    }
+
+    default fn get_account_phase_minted_amount(
+        &self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> u64 {
+        self.data::<Data>()
+            .phase_account_minted
+            .get((phase_id, account_id))
+            .unwrap_or(0)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_max_public_mint_per_account(
+        &mut self,
+        max_amount: Option<u64>,
+    ) -> Result<(), PSP34Error> {
+        let phase_id = self.get_last_phase_id()?;
+        self.data::<Data>().phases[phase_id as usize].max_per_account = max_amount;
+        Ok(())
+    }
+
+    default fn get_max_public_mint_per_account(&self) -> Option<u64> {
+        self.data::<Data>()
+            .phases
+            .last()
+            .and_then(|phase| phase.max_per_account)
+    }
+
+    default fn get_account_public_minted_amount(&self, account_id: AccountId) -> u64 {
+        match self.get_last_phase_id() {
+            Ok(phase_id) => self.get_account_phase_minted_amount(phase_id, account_id),
+            Err(_) => 0,
+        }
+    }
+
+    default fn get_account_prepresale_minting_amount(&self, account_id: AccountId) -> Option<u64> {
+        self.get_account_allowlist_minting_amount(PREPRESALE_PHASE, account_id)
+    }
+
+    default fn get_account_presale_minting_amount(&self, account_id: AccountId) -> Option<u64> {
+        self.get_account_allowlist_minting_amount(PRESALE_PHASE, account_id)
+    }
+
+    default fn get_prepresale_start_at(&self) -> u64 {
+        self.get_phase(PREPRESALE_PHASE)
+            .map(|phase| phase.start_at)
+            .unwrap_or_default()
+    }
+
+    default fn get_presale_start_at(&self) -> u64 {
+        self.get_phase(PRESALE_PHASE)
+            .map(|phase| phase.start_at)
+            .unwrap_or_default()
+    }
+
+    default fn get_public_sale_start_at(&self) -> u64 {
+        self.data::<Data>()
+            .phases
+            .last()
+            .map(|phase| phase.start_at)
+            .unwrap_or_default()
+    }
+
+    default fn get_public_sale_end_at(&self) -> Option<u64> {
+        self.data::<Data>()
+            .phases
+            .last()
+            .and_then(|phase| phase.end_at)
+    }
+
+    default fn prepresale_price(&self) -> Balance {
+        self.get_phase_price_internal(PREPRESALE_PHASE)
+            .unwrap_or_default()
+    }
+
+    default fn presale_price(&self) -> Balance {
+        self.get_phase_price_internal(PRESALE_PHASE)
+            .unwrap_or_default()
+    }
+
+    default fn get_phase_price(&self, phase_id: PhaseId) -> Result<Balance, PSP34Error> {
+        self.get_phase_price_internal(phase_id)
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_phase_dutch_auction(
+        &mut self,
+        phase_id: PhaseId,
+        dutch_auction: Option<DutchAuction>,
+    ) -> Result<(), PSP34Error> {
+        if self.get_current_minting_status() == MintingStatus::Phase(phase_id) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::PhaseAlreadyRunning.as_str(),
+            )));
+        }
+
+        let mut phases = self.data::<Data>().phases.clone();
+        phases
+            .get_mut(phase_id as usize)
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidPhase.as_str(),
+            )))?
+            .dutch_auction = dutch_auction;
+        self.check_phases(&phases)?;
+
+        self.data::<Data>().phases = phases;
+        Ok(())
+    }
+
+    default fn claim_rebate(&mut self, phase_id: PhaseId) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+
+        if !self.is_phase_over(phase_id) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::AuctionNotEnded.as_str(),
+            )));
+        }
+        let rebate = self.get_rebate_amount_internal(phase_id, caller_id);
+        if rebate == 0 {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::NoRebate.as_str(),
+            )));
+        }
+
+        let paid = self
+            .data::<Data>()
+            .auction_paid
+            .get((phase_id, caller_id))
+            .unwrap_or(0);
+        self.data::<Data>()
+            .auction_paid
+            .insert((phase_id, caller_id), &(paid - rebate));
+        let sales = self.data::<Data>().auction_sales.get(phase_id).unwrap_or(0);
+        self.data::<Data>()
+            .auction_sales
+            .insert(phase_id, &(sales - rebate));
+        self.data::<Data>().total_sales -= rebate;
+        let account_paid = self.data::<Data>().account_paid.get(caller_id).unwrap_or(0);
+        self.data::<Data>()
+            .account_paid
+            .insert(caller_id, &account_paid.saturating_sub(rebate));
+
+        Self::env()
+            .transfer(caller_id, rebate)
+            .map_err(|_| PSP34Error::Custom(String::from(Shiden34Error::RefundFailed.as_str())))
+    }
+
+    default fn get_rebate_amount(&self, phase_id: PhaseId, account_id: AccountId) -> Balance {
+        self.get_rebate_amount_internal(phase_id, account_id)
+    }
+
+    default fn get_launchpad_fee(&self) -> Percentage {
+        self.data::<Data>().launchpad_fee
+    }
+
+    default fn get_project_treasury_address(&self) -> AccountId {
+        self.data::<Data>().project_treasury.unwrap()
+    }
+
+    default fn get_launchpad_treasury_address(&self) -> AccountId {
+        self.data::<Data>().launchpad_treasury.unwrap()
+    }
+}
+
+/// Helper trait for Launchpad
+impl<T> Internal for T
+where
+    T: Storage<Data> + Storage<psp34::Data<enumerable::Balances>> + psp34::Internal,
+{
+    /// Check if the transferred mint values is as expected
+    default fn check_value(
+        &self,
+        transferred_value: u128,
+        mint_amount: u64,
+        minting_status: &MintingStatus,
+    ) -> Result<Balance, PSP34Error> {
+        let price = match minting_status {
+            MintingStatus::Phase(phase_id) => self.get_phase_price_internal(*phase_id)?,
+            _ => {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::UnableToMint.as_str(),
+                )))
+            }
+        };
+
+        self.check_value_at_price(transferred_value, mint_amount, price)
+    }
+
+    default fn check_value_at_price(
+        &self,
+        transferred_value: u128,
+        mint_amount: u64,
+        price: Balance,
+    ) -> Result<Balance, PSP34Error> {
+        if let Some(value) = (mint_amount as u128).checked_mul(price) {
+            if transferred_value == value
+                || (self.data::<Data>().accept_overpayment && transferred_value > value)
+            {
+                return Ok(value);
+            }
+        }
+        return Err(PSP34Error::Custom(String::from(
+            Shiden34Error::BadMintValue.as_str(),
+        )));
+    }
+
+    default fn refund_overpayment(
+        &self,
+        account_id: AccountId,
+        transferred_value: Balance,
+        mint_value: Balance,
+    ) -> Result<(), PSP34Error> {
+        if transferred_value <= mint_value {
+            return Ok(());
+        }
+        Self::env()
+            .transfer(account_id, transferred_value - mint_value)
+            .map_err(|_| PSP34Error::Custom(String::from(Shiden34Error::RefundFailed.as_str())))
+    }
+
+    /// Check amount of tokens to be minted
+    default fn check_amount(&self, mint_amount: u64) -> Result<(), PSP34Error> {
+        if mint_amount == 0 {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::CannotMintZeroTokens.as_str(),
+            )));
+        }
+        if mint_amount > self.data::<Data>().max_amount {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::TooManyTokensToMint.as_str(),
+            )));
+        }
+        let token_left = self.get_sale_supply_remaining_internal();
+        if mint_amount <= token_left {
+            return Ok(());
+        }
+        return Err(PSP34Error::Custom(String::from(
+            Shiden34Error::CollectionIsFull.as_str(),
+        )));
+    }
+
+    default fn get_mint_id(&mut self) -> Result<u64, PSP34Error> {
+        self.bind_metadata_commitment()?;
+        // untouched entries are stored in descending order, so the last one is the lowest id,
+        // shuffled and offset tokens only get mapped when their metadata is looked up
+        let token_length = self.data::<Data>().token_set_length;
+        let mint_id = self.remove_token_set_index(token_length - 1);
+        self.record_mint_entropy(mint_id);
+        Ok(mint_id)
+    }
+
+    default fn bind_metadata_commitment(&mut self) -> Result<(), PSP34Error> {
+        if self.data::<Data>().id_assignment == IdAssignment::Sequential
+            || self.data::<Data>().metadata_commitment.is_some()
+        {
+            return Ok(());
+        }
+        let commitment = self
+            .data::<Data>()
+            .randomness_commitment
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::RandomnessNotCommitted.as_str(),
+            )))?;
+        self.data::<Data>().metadata_commitment = Some(commitment);
+        self.data::<Data>().randomness_commitment = None;
+        Ok(())
+    }
+
+    default fn record_mint_entropy(&mut self, token_id: TokenId) {
+        if self.data::<Data>().id_assignment == IdAssignment::Sequential
+            || self.get_current_minting_status() == MintingStatus::End
+        {
+            return;
+        }
+        let input = (
+            self.data::<Data>().mint_entropy,
+            token_id,
+            Self::env().caller(),
+            Self::env().block_number(),
+            Self::env().block_timestamp(),
+        );
+        self.data::<Data>().mint_entropy = keccak256(&input.encode());
+    }
+
+    default fn reveal_metadata_seed(&mut self, secret: Vec<u8>) -> Result<(), PSP34Error> {
+        if self.get_current_minting_status() != MintingStatus::End {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::RandomnessRevealTooEarly.as_str(),
+            )));
+        }
+
+        let mut input: Vec<u8> = Vec::new();
+        input.extend_from_slice(&secret);
+        input.extend_from_slice(&self.data::<Data>().mint_entropy);
+        let seed = keccak256(&input);
+        self.data::<Data>().metadata_seed = Some(seed);
+        if self.data::<Data>().id_assignment == IdAssignment::Offset {
+            let max_supply = self.data::<Data>().max_supply;
+            self.data::<Data>().starting_index =
+                Some(DeterministicRandomness::new(seed).next_u64(max_supply - 1));
+        }
+        Ok(())
+    }
+
+    default fn get_metadata_id_internal(&self, token_id: TokenId) -> Option<u64> {
+        let max_supply = self.data::<Data>().max_supply;
+        // redeemed tokens are minted above the max supply and keep their own metadata
+        if token_id == 0 || token_id > max_supply {
+            return Some(token_id);
+        }
+        match self.data::<Data>().id_assignment {
+            IdAssignment::Sequential => Some(token_id),
+            IdAssignment::Random => self
+                .data::<Data>()
+                .metadata_seed
+                .map(|seed| shuffle_index(&seed, token_id - 1, max_supply) + 1),
+            IdAssignment::Offset => self.data::<Data>().starting_index.map(|starting_index| {
+                ((token_id as u128 - 1 + starting_index as u128) % max_supply as u128 + 1) as u64
+            }),
+        }
+    }
+
+    default fn get_token_set_value(&self, index: u64) -> TokenId {
+        self.data::<Data>()
+            .token_set
+            .get(index)
+            .unwrap_or(self.data::<Data>().max_supply - index)
+    }
+
+    /// Swap the last entry into `index` and pop, touching at most two storage cells
+    default fn remove_token_set_index(&mut self, index: u64) -> TokenId {
+        let last_index = self.data::<Data>().token_set_length - 1;
+        let token_id = self.get_token_set_value(index);
+
+        if index != last_index {
+            let last_token_id = self.get_token_set_value(last_index);
+            self.data::<Data>().token_set.insert(index, &last_token_id);
+            self.data::<Data>()
+                .token_set_position
+                .insert(last_token_id, &index);
+        }
+        self.data::<Data>().token_set.remove(last_index);
+        self.data::<Data>().token_set_position.remove(token_id);
+        self.data::<Data>().token_set_length = last_index;
+
+        token_id
+    }
+
+    default fn get_token_set_index(&self, token_id: TokenId) -> Option<u64> {
+        let max_supply = self.data::<Data>().max_supply;
+        if token_id == 0 || token_id > max_supply {
+            return None;
+        }
+
+        let index = self
+            .data::<Data>()
+            .token_set_position
+            .get(token_id)
+            .unwrap_or(max_supply - token_id);
+        if index < self.data::<Data>().token_set_length
+            && self.get_token_set_value(index) == token_id
+        {
+            return Some(index);
+        }
+        None
+    }
+
+    default fn check_id_choosable(&self) -> Result<(), PSP34Error> {
+        // sequential and offset draws pop the last entry as the lowest id, which a swap from
+        // the middle of the set would break
+        if self.data::<Data>().id_assignment != IdAssignment::Random {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::UnsupportedIdAssignment.as_str(),
+            )));
+        }
+        Ok(())
+    }
+
+    default fn add_token_set_value(&mut self, token_id: TokenId) {
+        let index = self.data::<Data>().token_set_length;
+        self.data::<Data>().token_set.insert(index, &token_id);
+        self.data::<Data>()
+            .token_set_position
+            .insert(token_id, &index);
+        self.data::<Data>().token_set_length = index + 1;
+    }
+
+    default fn record_payment(&mut self, account_id: AccountId, token_amount: u64, value: Balance) {
+        self.data::<Data>().total_sales += value;
+        let paid = self
+            .data::<Data>()
+            .account_paid
+            .get(account_id)
+            .unwrap_or(0);
+        self.data::<Data>()
+            .account_paid
+            .insert(account_id, &(paid + value));
+        let bought = self
+            .data::<Data>()
+            .account_bought
+            .get(account_id)
+            .unwrap_or(0);
+        self.data::<Data>()
+            .account_bought
+            .insert(account_id, &(bought + token_amount));
+    }
+
+    default fn record_token_sale(&mut self, id: TokenId, buyer: AccountId, price: Balance) {
+        self.data::<Data>().token_sales.insert(id, &(buyer, price));
+    }
+
+    default fn remove_token_sale(&mut self, id: &Id) {
+        if let Id::U64(token_id) = id {
+            self.data::<Data>().token_sales.remove(token_id);
+        }
+    }
+
+    default fn get_refundable_price(&self, token_id: TokenId) -> Result<Balance, PSP34Error> {
+        let (_, price) =
+            self.data::<Data>()
+                .token_sales
+                .get(token_id)
+                .ok_or(PSP34Error::Custom(String::from(
+                    Shiden34Error::NotRefundable.as_str(),
+                )))?;
+
+        // tokens of a rebate auction are refunded at most the clearing price,
+        // the rest of what was paid comes back through the rebate
+        let minting_type = self
+            .data::<Data>()
+            .minting_type_for_token
+            .get(token_id)
+            .unwrap_or(0);
+        if minting_type == 0 || minting_type as usize > MAX_PHASES {
+            return Ok(price);
+        }
+        let phase_id: PhaseId = minting_type - 1;
+        let rebate = match self.get_phase(phase_id) {
+            Ok(phase) => phase.dutch_auction.map_or(false, |auction| auction.rebate),
+            Err(_) => false,
+        };
+        if !rebate {
+            return Ok(price);
+        }
+        if !self.is_phase_over(phase_id) {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::AuctionNotEnded.as_str(),
+            )));
+        }
+        let clearing_price = self
+            .data::<Data>()
+            .auction_clearing_price
+            .get(phase_id)
+            .unwrap_or(price);
+        Ok(price.min(clearing_price))
+    }
+
+    default fn has_sale_started(&self) -> bool {
+        match self.data::<Data>().phases.first() {
+            Some(first_phase) => Self::env().block_timestamp() > first_phase.start_at,
+            None => false,
+        }
+    }
+
+    default fn is_sale_failed(&self) -> bool {
+        match self.data::<Data>().min_sales {
+            Some(min_sales) => {
+                // rebates owed to auction buyers do not count towards the soft cap
+                self.get_current_minting_status() == MintingStatus::End
+                    && self.get_total_withdraw_share_internal() < min_sales
+            }
+            None => false,
+        }
+    }
+
+    default fn get_raffle_shuffled_index(&self, phase_id: PhaseId, position: u64) -> u64 {
+        self.data::<Data>()
+            .raffle_shuffle
+            .get((phase_id, position))
+            .unwrap_or(position)
+    }
+
+    default fn is_raffle_drawn(&self, phase_id: PhaseId) -> bool {
+        match self.data::<Data>().raffle_winner_count.get(phase_id) {
+            Some(winner_count) => {
+                self.data::<Data>()
+                    .raffle_drawn_count
+                    .get(phase_id)
+                    .unwrap_or(0)
+                    == winner_count
+            }
+            None => false,
+        }
+    }
+
+    default fn is_raffle_draw_expired(&self, phase_id: PhaseId) -> bool {
+        let is_raffle = self
+            .get_phase(phase_id)
+            .map(|phase| phase.raffle)
+            .unwrap_or(false);
+        match self.get_phase_close_at(phase_id) {
+            Some(close_at) if is_raffle && !self.is_raffle_drawn(phase_id) => {
+                Self::env().block_timestamp()
+                    > close_at.saturating_add(self.data::<Data>().raffle_draw_timeout)
+            }
+            _ => false,
+        }
+    }
+
+    default fn close_raffle_draw(&mut self, phase_id: PhaseId) {
+        let drawn_count = self
+            .data::<Data>()
+            .raffle_drawn_count
+            .get(phase_id)
+            .unwrap_or(0);
+        if let Some(winner_count) = self.data::<Data>().raffle_winner_count.get(phase_id) {
+            let undrawn = winner_count - drawn_count;
+            self.data::<Data>().raffle_undrawn_supply -= undrawn;
+            let phase_minted = self.data::<Data>().phase_minted.get(phase_id).unwrap_or(0);
+            self.data::<Data>()
+                .phase_minted
+                .insert(phase_id, &(phase_minted - undrawn));
+        }
+        self.data::<Data>()
+            .raffle_winner_count
+            .insert(phase_id, &drawn_count);
+    }
+
+    default fn check_raffle_phase(&self, phase_id: PhaseId) -> Result<(), PSP34Error> {
+        if !self.get_phase(phase_id)?.raffle {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::NotRafflePhase.as_str(),
+            )));
+        }
+        Ok(())
+    }
+
+    default fn get_token_auction_internal(&self, id: TokenId) -> Result<TokenAuction, PSP34Error> {
+        self.data::<Data>()
+            .token_auctions
+            .get(id)
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::AuctionNotFound.as_str(),
+            )))
+    }
+
+    default fn mint_paid(
+        &mut self,
+        to: AccountId,
+        mint_amount: u64,
+        authorization: MintAuthorization,
+    ) -> Result<(), PSP34Error> {
+        let caller_id = Self::env().caller();
+        let minting_status = self.get_current_minting_status();
+        let transferred_value = Self::env().transferred_value();
+
+        self.check_amount(mint_amount)?;
+        let mint_value = match authorization {
+            MintAuthorization::Voucher(price) => {
+                self.check_value_at_price(transferred_value, mint_amount, price)?
+            }
+            _ => self.check_value(transferred_value, mint_amount, &minting_status)?,
+        };
+        self.check_and_update_allowed_to_mint(
+            caller_id,
+            mint_amount,
+            &minting_status,
+            &authorization,
+        )?;
+
+        for _ in 0..mint_amount {
+            let mint_id = self.get_mint_id()?;
+            self.data::<psp34::Data<enumerable::Balances>>()
+                ._mint_to(to, Id::U64(mint_id))?;
+            self._emit_transfer_event(None, Some(to), Id::U64(mint_id));
             self.data::<Data>()
-                .presale_whitelisted
-                .insert(account_id, &mint_amount);
+                .minting_type_for_token
+                .insert(mint_id, &minting_status.to_index());
+            self.record_token_sale(mint_id, caller_id, mint_value / mint_amount as Balance);
+        }
+
+        self.record_payment(caller_id, mint_amount, mint_value);
+        // voucher prices are set off-chain and must not move the clearing price
+        if !matches!(authorization, MintAuthorization::Voucher(_)) {
+            self.record_auction_sale(caller_id, &minting_status, mint_amount, mint_value)?;
+        }
+        self.refund_overpayment(caller_id, transferred_value, mint_value)?;
+        Ok(())
+    }
+
+    default fn use_voucher(
+        &mut self,
+        voucher: &MintVoucher,
+        signature: &[u8; 65],
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        if Self::env().block_timestamp() > voucher.expires_at {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::VoucherExpired.as_str(),
+            )));
+        }
+        if self
+            .data::<Data>()
+            .used_voucher_nonces
+            .contains(voucher.nonce)
+        {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::VoucherAlreadyUsed.as_str(),
+            )));
+        }
+
+        let message_hash = voucher_message_hash(&Self::env().account_id(), voucher);
+        let mut signer = [0u8; 33];
+        let is_signed = match self.data::<Data>().voucher_signer {
+            Some(voucher_signer) => {
+                ink::env::ecdsa_recover(signature, &message_hash, &mut signer).is_ok()
+                    && signer == voucher_signer
+            }
+            None => false,
+        };
+        if !is_signed
+            || voucher.account != Self::env().caller()
+            || self.get_current_minting_status() != MintingStatus::Phase(voucher.phase_id)
+        {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidVoucher.as_str(),
+            )));
+        }
+        if mint_amount > voucher.max_amount {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::TooManyTokensToMint.as_str(),
+            )));
+        }
+
+        self.data::<Data>()
+            .used_voucher_nonces
+            .insert(voucher.nonce, &());
+        Ok(())
+    }
+
+    default fn check_and_update_allowed_to_mint(
+        &mut self,
+        account_id: AccountId,
+        mint_amount: u64,
+        minting_status: &MintingStatus,
+        authorization: &MintAuthorization,
+    ) -> Result<(), PSP34Error> {
+        let phase_id = match minting_status {
+            MintingStatus::Phase(phase_id) => *phase_id,
+            _ => {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::UnableToMint.as_str(),
+                )))
+            }
+        };
+        let phase = self.get_phase(phase_id)?.clone();
+        // raffle phases only mint on the draw
+        if phase.raffle {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::UnableToMint.as_str(),
+            )));
         }
-        Ok(())
+
+        if let Some(remaining_allocation) = self.get_phase_remaining_allocation_internal(phase_id) {
+            if mint_amount > remaining_allocation {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::PhaseIsFull.as_str(),
+                )));
+            }
+        }
+        let phase_minted = self
+            .data::<Data>()
+            .phase_minted
+            .get(phase_id)
+            .unwrap_or(0)
+            .saturating_add(mint_amount);
+
+        let account_minted = self
+            .data::<Data>()
+            .phase_account_minted
+            .get((phase_id, account_id))
+            .unwrap_or(0)
+            .saturating_add(mint_amount);
+        if let Some(max_amount) = phase.max_per_account {
+            if account_minted > max_amount {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::AccountMintLimitExceeded.as_str(),
+                )));
+            }
+        }
+
+        match authorization {
+            MintAuthorization::MerkleProof(allocation) => {
+                let claimed = self
+                    .data::<Data>()
+                    .allowlist_claimed
+                    .get((phase_id, account_id))
+                    .unwrap_or(0)
+                    .saturating_add(mint_amount);
+                if claimed > *allocation {
+                    return Err(PSP34Error::Custom(String::from(
+                        Shiden34Error::UnableToMint.as_str(),
+                    )));
+                }
+                self.data::<Data>()
+                    .allowlist_claimed
+                    .insert((phase_id, account_id), &claimed);
+            }
+            MintAuthorization::Allowlist | MintAuthorization::TokenGate(_)
+                if phase.allowlist_only =>
+            {
+                let mint_slot = self
+                    .data::<Data>()
+                    .allowlist
+                    .get((phase_id, account_id))
+                    .unwrap_or(0);
+
+                if mint_slot >= mint_amount {
+                    self.data::<Data>()
+                        .allowlist
+                        .insert((phase_id, account_id), &(mint_slot - mint_amount));
+                } else {
+                    // holders of partner collections make up for missing slots with their tokens
+                    let gated_tokens: &[(AccountId, Id)] = match authorization {
+                        MintAuthorization::TokenGate(gated_tokens) => gated_tokens.as_slice(),
+                        _ => &[],
+                    };
+                    self.claim_gated_slots(
+                        phase_id,
+                        account_id,
+                        mint_amount - mint_slot,
+                        gated_tokens,
+                    )?;
+                    if mint_slot > 0 {
+                        self.data::<Data>()
+                            .allowlist
+                            .insert((phase_id, account_id), &0);
+                    }
+                }
+            }
+            // a voucher allows the mint in place of the allowlist
+            _ => {}
+        }
+
+        self.data::<Data>()
+            .phase_minted
+            .insert(phase_id, &phase_minted);
+        self.data::<Data>()
+            .phase_account_minted
+            .insert((phase_id, account_id), &account_minted);
+        return Ok(());
     }
 
-    #[modifiers(only_owner)]
-    default fn add_account_to_presale(
-        &mut self,
-        account_id: AccountId,
-        mint_amount: u64,
-    ) -> Result<(), PSP34Error> {
+    default fn get_current_minting_status(&self) -> MintingStatus {
+        if let Some(minting_status) = self.data::<Data>().forced_minting_status {
+            return minting_status;
+        }
+        let phases = &self.data::<Data>().phases;
+        if self.get_sale_supply_remaining_internal() == 0 {
+            return MintingStatus::End;
+        }
+        let current_timestamp = Self::env().block_timestamp();
+
+        // the running phase is the last one which has started
+        match phases
+            .iter()
+            .rposition(|phase| current_timestamp > phase.start_at)
+        {
+            None => return MintingStatus::Closed,
+            Some(phase_id) => {
+                if current_timestamp <= phases[phase_id].end_at.unwrap_or(u64::MAX) {
+                    return MintingStatus::Phase(phase_id as PhaseId);
+                } else if phase_id + 1 == phases.len() {
+                    return MintingStatus::End;
+                } else {
+                    return MintingStatus::Closed;
+                }
+            }
+        }
+    }
+
+    default fn get_phase(&self, phase_id: PhaseId) -> Result<&Phase, PSP34Error> {
         self.data::<Data>()
-            .presale_whitelisted
-            .insert(account_id, &mint_amount);
+            .phases
+            .get(phase_id as usize)
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidPhase.as_str(),
+            )))
+    }
+
+    default fn check_phases(&self, phases: &Vec<Phase>) -> Result<(), PSP34Error> {
+        if phases.len() > MAX_PHASES {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidSchedule.as_str(),
+            )));
+        }
+        for (index, phase) in phases.iter().enumerate() {
+            let next_start_at = phases.get(index + 1).map(|next_phase| next_phase.start_at);
+            let valid_end = match phase.end_at {
+                Some(end_at) => {
+                    end_at > phase.start_at && end_at <= next_start_at.unwrap_or(u64::MAX)
+                }
+                None => true,
+            };
+            let valid_auction = match phase.dutch_auction {
+                Some(auction) => auction.step_interval > 0 && auction.floor_price <= phase.price,
+                None => true,
+            };
+            // a raffle deposit is the price of the phase, so it has to be fixed, and the draw
+            // timeout runs from the phase close
+            let valid_raffle = !phase.raffle
+                || (phase.dutch_auction.is_none()
+                    && (phase.end_at.is_some() || next_start_at.is_some()));
+            if !valid_end
+                || !valid_auction
+                || !valid_raffle
+                || phase.start_at > next_start_at.unwrap_or(u64::MAX)
+            {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::InvalidSchedule.as_str(),
+                )));
+            }
+        }
         Ok(())
     }
 
-    #[modifiers(only_owner)]
-    default fn set_minting_status(
+    default fn set_phase_schedule(
         &mut self,
-        minting_status_index: Option<u8>,
+        phase_id: PhaseId,
+        start_at: u64,
+        end_at: Option<u64>,
     ) -> Result<(), PSP34Error> {
-        self.data::<Data>().forced_minting_status = minting_status_index;
-        return Ok(());
+        let mut phases = self.data::<Data>().phases.clone();
+        let phase = phases
+            .get_mut(phase_id as usize)
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidPhase.as_str(),
+            )))?;
+        phase.start_at = start_at;
+        phase.end_at = end_at;
+        self.update_phases(phases)
     }
 
-    default fn get_minting_status(&self) -> String {
+    default fn update_phases(&mut self, phases: Vec<Phase>) -> Result<(), PSP34Error> {
+        self.check_phases(&phases)?;
+        // a forced phase has to stay in the schedule
+        if let Some(MintingStatus::Phase(phase_id)) = self.data::<Data>().forced_minting_status {
+            if phase_id as usize >= phases.len() {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::InvalidPhase.as_str(),
+                )));
+            }
+        }
+
+        let current_timestamp = Self::env().block_timestamp();
         let minting_status = self.get_current_minting_status();
-        match minting_status {
-            MintingStatus::Closed => return "closed".as_bytes().to_vec(),
-            MintingStatus::Prepresale => return "prepresale".as_bytes().to_vec(),
-            MintingStatus::Presale => return "presale".as_bytes().to_vec(),
-            MintingStatus::Public => return "public".as_bytes().to_vec(),
-            MintingStatus::End => return "end".as_bytes().to_vec(),
+        let old_phases = self.data::<Data>().phases.clone();
+        for (index, old_phase) in old_phases.iter().enumerate() {
+            let running = minting_status == MintingStatus::Phase(index as PhaseId);
+            if !running && old_phase.start_at >= current_timestamp {
+                continue;
+            }
+            // buyers of a started phase keep its terms, only the running phase can be
+            // extended or cut short
+            let kept = phases.get(index).map_or(false, |phase| {
+                let valid_end = phase.end_at == old_phase.end_at
+                    || (running
+                        && phase
+                            .end_at
+                            .map_or(true, |end_at| end_at > current_timestamp));
+                valid_end
+                    && Phase {
+                        end_at: old_phase.end_at,
+                        ..phase.clone()
+                    } == *old_phase
+            });
+            if !kept {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::PhaseAlreadyRunning.as_str(),
+                )));
+            }
+        }
+        for (index, phase) in phases.iter().enumerate() {
+            // starting a pending phase right away would silently cut the running one short
+            let moved = old_phases
+                .get(index)
+                .map_or(true, |old_phase| old_phase.start_at != phase.start_at);
+            if moved && phase.start_at <= current_timestamp {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::InvalidSchedule.as_str(),
+                )));
+            }
         }
-    }
 
-    default fn get_account_prepresale_minting_amount(&self, account_id: AccountId) -> Option<u64> {
-        self.data::<Data>().prepresale_whitelisted.get(account_id)
+        for index in 0..old_phases.len().max(phases.len()) {
+            let old_schedule = old_phases
+                .get(index)
+                .map(|phase| (phase.start_at, phase.end_at));
+            let new_schedule = phases
+                .get(index)
+                .map(|phase| (phase.start_at, phase.end_at));
+            if old_schedule != new_schedule {
+                self._emit_schedule_changed_event(index as PhaseId, old_schedule, new_schedule);
+            }
+        }
+        self.data::<Data>().phases = phases;
+        Ok(())
     }
 
-    default fn get_account_presale_minting_amount(&self, account_id: AccountId) -> Option<u64> {
-        self.data::<Data>().presale_whitelisted.get(account_id)
+    default fn get_phase_remaining_allocation_internal(&self, phase_id: PhaseId) -> Option<u64> {
+        let phases = &self.data::<Data>().phases;
+        if phases.get(phase_id as usize)?.max_supply.is_none() {
+            return None;
+        }
+
+        let mut allocated: u64 = 0;
+        let mut minted: u64 = 0;
+        for (index, phase) in phases.iter().enumerate().take(phase_id as usize + 1) {
+            if let Some(max_supply) = phase.max_supply {
+                allocated = allocated.saturating_add(max_supply);
+                minted += self
+                    .data::<Data>()
+                    .phase_minted
+                    .get(index as PhaseId)
+                    .unwrap_or(0);
+            }
+        }
+        Some(allocated.saturating_sub(minted))
     }
 
-    default fn get_prepresale_start_at(&self) -> u64 {
-        self.data::<Data>().prepresale_start_at
+    default fn get_phase_price_internal(&self, phase_id: PhaseId) -> Result<Balance, PSP34Error> {
+        let phase = self.get_phase(phase_id)?;
+        match phase.dutch_auction {
+            None => Ok(phase.price),
+            Some(auction) => {
+                let steps = Self::env().block_timestamp().saturating_sub(phase.start_at)
+                    / auction.step_interval;
+                let discount = auction.price_step.saturating_mul(steps as u128);
+                Ok(phase
+                    .price
+                    .saturating_sub(discount)
+                    .max(auction.floor_price))
+            }
+        }
     }
 
-    default fn get_presale_start_at(&self) -> u64 {
-        self.data::<Data>().presale_start_at
+    default fn get_phase_close_at(&self, phase_id: PhaseId) -> Option<u64> {
+        let phases = &self.data::<Data>().phases;
+        phases.get(phase_id as usize).and_then(|phase| {
+            phase.end_at.or(phases
+                .get(phase_id as usize + 1)
+                .map(|next_phase| next_phase.start_at))
+        })
     }
 
-    default fn get_public_sale_start_at(&self) -> u64 {
-        self.data::<Data>().public_sale_start_at
+    default fn is_phase_over(&self, phase_id: PhaseId) -> bool {
+        match self.get_current_minting_status() {
+            MintingStatus::End => true,
+            MintingStatus::Phase(current_phase_id) => current_phase_id > phase_id,
+            MintingStatus::Closed => match self.get_phase(phase_id) {
+                Ok(phase) => Self::env().block_timestamp() > phase.end_at.unwrap_or(u64::MAX),
+                Err(_) => false,
+            },
+        }
     }
 
-    default fn get_public_sale_end_at(&self) -> Option<u64> {
-        self.data::<Data>().public_sale_end_at
+    default fn record_auction_sale(
+        &mut self,
+        account_id: AccountId,
+        minting_status: &MintingStatus,
+        mint_amount: u64,
+        mint_value: Balance,
+    ) -> Result<(), PSP34Error> {
+        let phase_id = match minting_status {
+            MintingStatus::Phase(phase_id) => *phase_id,
+            _ => return Ok(()),
+        };
+        let rebate = match self.get_phase(phase_id)?.dutch_auction {
+            Some(auction) => auction.rebate,
+            None => false,
+        };
+        if !rebate {
+            return Ok(());
+        }
+
+        let price = self.get_phase_price_internal(phase_id)?;
+        let paid = self
+            .data::<Data>()
+            .auction_paid
+            .get((phase_id, account_id))
+            .unwrap_or(0);
+        self.data::<Data>()
+            .auction_paid
+            .insert((phase_id, account_id), &(paid + mint_value));
+        let sales = self.data::<Data>().auction_sales.get(phase_id).unwrap_or(0);
+        self.data::<Data>()
+            .auction_sales
+            .insert(phase_id, &(sales + mint_value));
+        let minted = self
+            .data::<Data>()
+            .auction_minted
+            .get(phase_id)
+            .unwrap_or(0);
+        self.data::<Data>()
+            .auction_minted
+            .insert(phase_id, &(minted + mint_amount));
+        let account_minted = self
+            .data::<Data>()
+            .auction_account_minted
+            .get((phase_id, account_id))
+            .unwrap_or(0);
+        self.data::<Data>()
+            .auction_account_minted
+            .insert((phase_id, account_id), &(account_minted + mint_amount));
+        // the price never goes up, so the latest mint sets the clearing price
+        self.data::<Data>()
+            .auction_clearing_price
+            .insert(phase_id, &price);
+        Ok(())
     }
 
-    default fn prepresale_price(&self) -> Balance {
-        self.data::<Data>().prepresale_price_per_mint
+    default fn get_rebate_amount_internal(
+        &self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> Balance {
+        let clearing_price = self
+            .data::<Data>()
+            .auction_clearing_price
+            .get(phase_id)
+            .unwrap_or(0);
+        let minted = self
+            .data::<Data>()
+            .auction_account_minted
+            .get((phase_id, account_id))
+            .unwrap_or(0);
+        self.data::<Data>()
+            .auction_paid
+            .get((phase_id, account_id))
+            .unwrap_or(0)
+            .saturating_sub(clearing_price.saturating_mul(minted as u128))
     }
 
-    default fn presale_price(&self) -> Balance {
-        self.data::<Data>().presale_price_per_mint
+    default fn get_outstanding_rebates_internal(&self) -> Balance {
+        let mut outstanding: Balance = 0;
+        for phase_id in 0..self.data::<Data>().phases.len() as PhaseId {
+            let clearing_price = self
+                .data::<Data>()
+                .auction_clearing_price
+                .get(phase_id)
+                .unwrap_or(0);
+            let minted = self
+                .data::<Data>()
+                .auction_minted
+                .get(phase_id)
+                .unwrap_or(0);
+            outstanding += self
+                .data::<Data>()
+                .auction_sales
+                .get(phase_id)
+                .unwrap_or(0)
+                .saturating_sub(clearing_price.saturating_mul(minted as u128));
+        }
+        outstanding
     }
 
-    default fn get_launchpad_fee(&self) -> Percentage {
-        self.data::<Data>().launchpad_fee
+    default fn get_last_phase_id(&self) -> Result<PhaseId, PSP34Error> {
+        match self.data::<Data>().phases.len() {
+            0 => Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidPhase.as_str(),
+            ))),
+            phase_count => Ok((phase_count - 1) as PhaseId),
+        }
     }
 
-    default fn get_project_treasury_address(&self) -> AccountId {
-        self.data::<Data>().project_treasury.unwrap()
+    default fn set_allowlist_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        self.get_phase(phase_id)?;
+        let old_amount = self.data::<Data>().allowlist.get((phase_id, account_id));
+        if old_amount.is_none() {
+            let length = self
+                .data::<Data>()
+                .allowlist_length
+                .get(phase_id)
+                .unwrap_or(0);
+            self.data::<Data>()
+                .allowlist_accounts
+                .insert((phase_id, length), &account_id);
+            self.data::<Data>()
+                .allowlist_account_position
+                .insert((phase_id, account_id), &length);
+            self.data::<Data>()
+                .allowlist_length
+                .insert(phase_id, &(length + 1));
+        }
+        self.data::<Data>()
+            .allowlist
+            .insert((phase_id, account_id), &mint_amount);
+        self._emit_allowlist_updated_event(phase_id, account_id, old_amount, Some(mint_amount));
+        Ok(())
     }
 
-    default fn get_launchpad_treasury_address(&self) -> AccountId {
-        self.data::<Data>().launchpad_treasury.unwrap()
+    default fn increase_allowlist_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error> {
+        let new_amount = self
+            .data::<Data>()
+            .allowlist
+            .get((phase_id, account_id))
+            .unwrap_or(0)
+            .checked_add(mint_amount)
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::BadAllowlistAmount.as_str(),
+            )))?;
+        self.set_allowlist_slots(phase_id, account_id, new_amount)
     }
-}
 
-/// Helper trait for Launchpad
-impl<T> Internal for T
-where
-    T: Storage<Data> + Storage<psp34::Data<enumerable::Balances>>,
-{
-    /// Check if the transferred mint values is as expected
-    default fn check_value(
-        &self,
-        transferred_value: u128,
+    default fn decrease_allowlist_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
         mint_amount: u64,
-        minting_status: &MintingStatus,
     ) -> Result<(), PSP34Error> {
-        let price = match minting_status {
-            MintingStatus::Prepresale => self.data::<Data>().prepresale_price_per_mint,
-            MintingStatus::Presale => self.data::<Data>().presale_price_per_mint,
-            MintingStatus::Public => self.data::<Data>().price_per_mint,
-            _ => {
-                return Err(PSP34Error::Custom(String::from(
-                    Shiden34Error::UnableToMint.as_str(),
-                )))
-            }
-        };
+        let old_amount = self
+            .data::<Data>()
+            .allowlist
+            .get((phase_id, account_id))
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::NotAllowlisted.as_str(),
+            )))?;
+        let new_amount = old_amount
+            .checked_sub(mint_amount)
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::BadAllowlistAmount.as_str(),
+            )))?;
+        self.set_allowlist_slots(phase_id, account_id, new_amount)
+    }
 
-        if let Some(value) = (mint_amount as u128).checked_mul(price) {
-            if transferred_value == value {
-                return Ok(());
-            }
+    default fn get_gated_token_owner(&self, collection: AccountId, id: Id) -> Option<AccountId> {
+        match psp34::PSP34Ref::owner_of_builder(&collection, id).try_invoke() {
+            Ok(Ok(owner)) => owner,
+            _ => None,
         }
-        return Err(PSP34Error::Custom(String::from(
-            Shiden34Error::BadMintValue.as_str(),
-        )));
     }
 
-    /// Check amount of tokens to be minted
-    default fn check_amount(&self, mint_amount: u64) -> Result<(), PSP34Error> {
-        if mint_amount == 0 {
+    default fn claim_gated_slots(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+        gated_tokens: &[(AccountId, Id)],
+    ) -> Result<(), PSP34Error> {
+        if gated_tokens.len() > MAX_GATED_TOKENS {
             return Err(PSP34Error::Custom(String::from(
-                Shiden34Error::CannotMintZeroTokens.as_str(),
+                Shiden34Error::TooManyGatedTokens.as_str(),
             )));
         }
-        if mint_amount > self.data::<Data>().max_amount {
+        let token_gates = self
+            .data::<Data>()
+            .token_gates
+            .get(phase_id)
+            .unwrap_or_default();
+        let mut remaining = mint_amount;
+        let mut claims: Vec<((PhaseId, AccountId, Id), u64)> = Vec::new();
+        for (index, (collection, id)) in gated_tokens.iter().enumerate() {
+            if remaining == 0 {
+                break;
+            }
+            let gate = token_gates
+                .iter()
+                .find(|gate| gate.collection == *collection);
+            let gate = match gate {
+                Some(gate) if !gated_tokens[..index].contains(&(*collection, id.clone())) => gate,
+                _ => {
+                    return Err(PSP34Error::Custom(String::from(
+                        Shiden34Error::InvalidTokenGate.as_str(),
+                    )))
+                }
+            };
+            if self.get_gated_token_owner(*collection, id.clone()) != Some(account_id) {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::GatedTokenNotOwned.as_str(),
+                )));
+            }
+
+            let key = (phase_id, *collection, id.clone());
+            let claimed = self
+                .data::<Data>()
+                .gated_token_claimed
+                .get(key.clone())
+                .unwrap_or(0);
+            let taken = gate.slots_per_token.saturating_sub(claimed).min(remaining);
+            if taken > 0 {
+                remaining -= taken;
+                claims.push((key, claimed + taken));
+            }
+        }
+        if remaining > 0 {
             return Err(PSP34Error::Custom(String::from(
-                Shiden34Error::TooManyTokensToMint.as_str(),
+                Shiden34Error::UnableToMint.as_str(),
             )));
         }
-        let token_left = self.data::<Data>().token_set.len().clone() as u64;
-        if mint_amount <= token_left {
-            return Ok(());
-        }
-        return Err(PSP34Error::Custom(String::from(
-            Shiden34Error::CollectionIsFull.as_str(),
-        )));
-    }
-
-    default fn get_pseudo_random(&mut self, max_value: u64) -> u64 {
-        let seed = Self::env().block_timestamp();
-        let mut input: Vec<u8> = Vec::new();
-        input.extend_from_slice(&seed.to_be_bytes());
-        input.extend_from_slice(&self.data::<Data>().pseudo_random_salt.to_be_bytes());
-        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
-        hash_bytes::<hash::Keccak256>(&input, &mut output);
-        self.data::<Data>().pseudo_random_salt += 1;
-
-        // hacky, have to find another way
-        let number = (output[0] as u64 * output[1] as u64) % (max_value + 1);
-        number
-    }
 
-    default fn get_mint_id(&mut self) -> u64 {
-        let token_length = self.data::<Data>().token_set.len().clone() as u64;
-        let token_set_idx = self.get_pseudo_random(token_length - 1);
-        self.data::<Data>()
-            .token_set
-            .swap_remove(token_set_idx as usize) as u64
+        for (key, claimed) in claims {
+            self.data::<Data>()
+                .gated_token_claimed
+                .insert(key, &claimed);
+        }
+        Ok(())
     }
 
-    default fn check_and_update_allowed_to_mint(
+    default fn remove_allowlist_account(
         &mut self,
+        phase_id: PhaseId,
         account_id: AccountId,
-        mint_amount: u64,
-        minting_status: &MintingStatus,
     ) -> Result<(), PSP34Error> {
-        match minting_status {
-            MintingStatus::Closed => {
-                return Err(PSP34Error::Custom(String::from(
-                    Shiden34Error::UnableToMint.as_str(),
-                )))
-            }
-            MintingStatus::End => {
+        let old_amount = self.data::<Data>().allowlist.get((phase_id, account_id));
+        let position = self
+            .data::<Data>()
+            .allowlist_account_position
+            .get((phase_id, account_id));
+        let (old_amount, position) = match (old_amount, position) {
+            (Some(old_amount), Some(position)) => (old_amount, position),
+            _ => {
                 return Err(PSP34Error::Custom(String::from(
-                    Shiden34Error::UnableToMint.as_str(),
+                    Shiden34Error::NotAllowlisted.as_str(),
                 )))
             }
-            MintingStatus::Prepresale => {
-                let mint_slot = self
-                    .data::<Data>()
-                    .prepresale_whitelisted
-                    .get(account_id)
-                    .unwrap_or(0);
+        };
 
-                if mint_slot < mint_amount {
-                    return Err(PSP34Error::Custom(String::from(
-                        Shiden34Error::UnableToMint.as_str(),
-                    )));
-                }
-                self.data::<Data>()
-                    .prepresale_whitelisted
-                    .insert(account_id, &(mint_slot - mint_amount));
-            }
-            MintingStatus::Presale => {
-                let mint_slot = self
-                    .data::<Data>()
-                    .presale_whitelisted
-                    .get(account_id)
-                    .unwrap_or(0);
+        // move the last account into the freed position
+        let last_position = self
+            .data::<Data>()
+            .allowlist_length
+            .get(phase_id)
+            .unwrap_or(0)
+            - 1;
+        if position != last_position {
+            let last_account = self
+                .data::<Data>()
+                .allowlist_accounts
+                .get((phase_id, last_position))
+                .unwrap();
+            self.data::<Data>()
+                .allowlist_accounts
+                .insert((phase_id, position), &last_account);
+            self.data::<Data>()
+                .allowlist_account_position
+                .insert((phase_id, last_account), &position);
+        }
+        self.data::<Data>()
+            .allowlist_accounts
+            .remove((phase_id, last_position));
+        self.data::<Data>()
+            .allowlist_account_position
+            .remove((phase_id, account_id));
+        self.data::<Data>()
+            .allowlist_length
+            .insert(phase_id, &last_position);
+        self.data::<Data>().allowlist.remove((phase_id, account_id));
+        self._emit_allowlist_updated_event(phase_id, account_id, Some(old_amount), None);
+        Ok(())
+    }
 
-                if mint_slot < mint_amount {
-                    return Err(PSP34Error::Custom(String::from(
-                        Shiden34Error::UnableToMint.as_str(),
-                    )));
-                }
-                self.data::<Data>()
-                    .presale_whitelisted
-                    .insert(account_id, &(mint_slot - mint_amount));
-            }
-            MintingStatus::Public => {
-                return Ok(());
+    fn get_reserve_remaining_internal(&self) -> u64 {
+        self.data::<Data>().reserved_supply - self.data::<Data>().reserved_minted
+    }
+
+    fn get_sale_supply_remaining_internal(&self) -> u64 {
+        self.data::<Data>()
+            .token_set_length
+            .saturating_sub(self.get_reserve_remaining_internal())
+            .saturating_sub(self.data::<Data>().raffle_undrawn_supply)
+    }
+
+    default fn check_owned_tokens(
+        &self,
+        account_id: AccountId,
+        ids: &Vec<Id>,
+    ) -> Result<(), PSP34Error> {
+        for (index, id) in ids.iter().enumerate() {
+            let owner = self
+                .data::<psp34::Data<enumerable::Balances>>()
+                .owner_of(id.clone())
+                .ok_or(PSP34Error::TokenNotExists)?;
+            if owner != account_id || ids[..index].contains(id) {
+                return Err(PSP34Error::NotApproved);
             }
         }
+        Ok(())
+    }
 
-        return Ok(());
+    default fn _emit_schedule_changed_event(
+        &self,
+        _phase_id: PhaseId,
+        _old_schedule: Option<PhaseSchedule>,
+        _new_schedule: Option<PhaseSchedule>,
+    ) {
     }
 
-    default fn get_current_minting_status(&self) -> MintingStatus {
-        if let Some(minting_status) = self.data::<Data>().forced_minting_status {
-            return MintingStatus::from(minting_status);
-        }
-        let current_timestamp = Self::env().block_timestamp();
+    default fn _emit_minting_status_forced_event(&self, _minting_status: Option<MintingStatus>) {}
 
-        if current_timestamp > self.data::<Data>().public_sale_end_at.unwrap_or(u64::MAX)
-            || u128::from(self.data::<Data>().max_supply)
-                == self
-                    .data::<psp34::Data<enumerable::Balances>>()
-                    .total_supply()
-        {
-            // or if token supply is depleted
-            return MintingStatus::End;
-        } else if current_timestamp > self.data::<Data>().public_sale_start_at {
-            return MintingStatus::Public;
-        } else if current_timestamp > self.data::<Data>().presale_start_at {
-            return MintingStatus::Presale;
-        } else if current_timestamp > self.data::<Data>().prepresale_start_at {
-            return MintingStatus::Prepresale;
-        } else {
-            return MintingStatus::Closed;
-        }
+    default fn _emit_voucher_signer_changed_event(&self, _voucher_signer: Option<[u8; 33]>) {}
+
+    default fn _emit_allowlist_updated_event(
+        &self,
+        _phase_id: PhaseId,
+        _account_id: AccountId,
+        _old_amount: Option<u64>,
+        _new_amount: Option<u64>,
+    ) {
     }
+
+    default fn _emit_redeemed_event(
+        &self,
+        _account_id: AccountId,
+        _tier: RedemptionTierId,
+        _burned_ids: Vec<Id>,
+        _id: Id,
+    ) {
+    }
+
     fn get_available_to_withdraw_launchpad_internal(&self) -> Balance {
         let minting_status = self.get_current_minting_status();
-        if minting_status != MintingStatus::End {
+        if minting_status != MintingStatus::End || self.is_sale_failed() {
+            return 0;
+        }
+        #[cfg(feature = "refundable")]
+        if RefundableInternal::is_refund_window_open(self) {
             return 0;
         }
 
@@ -497,12 +3000,16 @@ where
         let launchpad_share =
             (total_withdraw_share * self.data::<Data>().launchpad_fee).saturating_div(100);
 
-        launchpad_share - self.data::<Data>().withdrawn_sales_launchpad
+        launchpad_share.saturating_sub(self.data::<Data>().withdrawn_sales_launchpad)
     }
 
     fn get_available_to_withdraw_project_internal(&self) -> Balance {
         let minting_status = self.get_current_minting_status();
-        if minting_status != MintingStatus::End {
+        if minting_status != MintingStatus::End || self.is_sale_failed() {
+            return 0;
+        }
+        #[cfg(feature = "refundable")]
+        if RefundableInternal::is_refund_window_open(self) {
             return 0;
         }
 
@@ -510,11 +3017,15 @@ where
         let project_share =
             (total_withdraw_share * (100 - self.data::<Data>().launchpad_fee)).saturating_div(100);
 
-        project_share - self.data::<Data>().withdrawn_sales_project
+        project_share.saturating_sub(self.data::<Data>().withdrawn_sales_project)
     }
 
     fn get_total_withdraw_share_internal(&self) -> u128 {
-        let total_withdraw_share: u128 = self.data::<Data>().total_sales;
+        // rebates owed to auction buyers are not part of the sales
+        let total_withdraw_share: u128 = self
+            .data::<Data>()
+            .total_sales
+            .saturating_sub(self.get_outstanding_rebates_internal());
         return total_withdraw_share;
     }
 }
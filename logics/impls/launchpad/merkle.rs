@@ -0,0 +1,28 @@
+use crate::impls::launchpad::randomness::keccak256;
+use ink::prelude::vec::Vec;
+use openbrush::traits::AccountId;
+use scale::Encode;
+
+pub type MerkleHash = [u8; 32];
+
+/// Leaf of an allowlist tree, the keccak256 of the SCALE encoded `(account_id, allocation)`
+pub fn allowlist_leaf(account_id: &AccountId, allocation: u64) -> MerkleHash {
+    keccak256(&(account_id, allocation).encode())
+}
+
+/// Hash two nodes in sorted order, so that proofs do not need to carry the side of each sibling
+pub fn hash_pair(a: &MerkleHash, b: &MerkleHash) -> MerkleHash {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut input: Vec<u8> = Vec::new();
+    input.extend_from_slice(left);
+    input.extend_from_slice(right);
+    keccak256(&input)
+}
+
+/// Hash a leaf up the tree with the sibling of each level and compare with the root
+pub fn verify_proof(root: &MerkleHash, leaf: MerkleHash, proof: &[MerkleHash]) -> bool {
+    let computed_root = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed_root == *root
+}
@@ -1,2 +1,7 @@
 pub mod launchpad;
+pub mod merkle;
+pub mod randomness;
+#[cfg(feature = "refundable")]
+pub mod refundable;
 pub mod types;
+pub mod voucher;
@@ -0,0 +1,114 @@
+use ink::env::{hash, hash_bytes};
+use ink::prelude::vec::Vec;
+
+pub type RandomSeed = [u8; 32];
+
+const FEISTEL_ROUNDS: u8 = 4;
+
+/// Source of entropy used for draws
+pub trait RandomnessSource {
+    /// Get the next 32 bytes of entropy, every call must return a fresh value
+    fn next_seed(&mut self) -> RandomSeed;
+
+    /// Get a number uniformly distributed in `0..=max_value`
+    fn next_u64(&mut self, max_value: u64) -> u64 {
+        let mut seed = self.next_seed();
+        loop {
+            if let Some(number) = reduce_uniform(&seed, max_value) {
+                return number;
+            }
+            seed = keccak256(&seed);
+        }
+    }
+}
+
+/// Reproducible hash chain over a revealed seed, so that anyone can replay a draw
+pub struct DeterministicRandomness {
+    seed: RandomSeed,
+    counter: u64,
+}
+
+impl DeterministicRandomness {
+    pub fn new(seed: RandomSeed) -> Self {
+        Self { seed, counter: 0 }
+    }
+
+    /// Resume the hash chain at `counter`, for draws split across calls
+    pub fn with_counter(seed: RandomSeed, counter: u64) -> Self {
+        Self { seed, counter }
+    }
+}
+
+impl RandomnessSource for DeterministicRandomness {
+    fn next_seed(&mut self) -> RandomSeed {
+        let mut input: Vec<u8> = Vec::new();
+        input.extend_from_slice(&self.seed);
+        input.extend_from_slice(&self.counter.to_be_bytes());
+        self.counter += 1;
+        keccak256(&input)
+    }
+}
+
+/// Map `index` to its place in a permutation of `0..size` keyed by `seed`, through a balanced
+/// Feistel network over the smallest power of four covering `size`, walking the cycle back into
+/// range when a value falls outside it
+pub fn shuffle_index(seed: &RandomSeed, index: u64, size: u64) -> u64 {
+    let mut half_bits = 1;
+    while 1u128 << (2 * half_bits) < size as u128 {
+        half_bits += 1;
+    }
+    let mask = (1u64 << half_bits) - 1;
+
+    let mut value = index;
+    loop {
+        let mut left = value >> half_bits;
+        let mut right = value & mask;
+        for round in 0..FEISTEL_ROUNDS {
+            let mut input: Vec<u8> = Vec::new();
+            input.extend_from_slice(seed);
+            input.push(round);
+            input.extend_from_slice(&right.to_be_bytes());
+            let mut round_bytes = [0u8; 8];
+            round_bytes.copy_from_slice(&keccak256(&input)[..8]);
+            let next_right = left ^ (u64::from_be_bytes(round_bytes) & mask);
+            left = right;
+            right = next_right;
+        }
+        value = (left << half_bits) | right;
+        if value < size {
+            return value;
+        }
+    }
+}
+
+pub fn keccak256(input: &[u8]) -> RandomSeed {
+    let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+    hash_bytes::<hash::Keccak256>(input, &mut output);
+    output
+}
+
+/// Reduce a 256-bit big-endian seed into `0..=max_value`.
+/// Returns `None` when the seed falls into the biased tail at the top of the
+/// hash space, in which case the caller has to draw again.
+pub fn reduce_uniform(seed: &RandomSeed, max_value: u64) -> Option<u64> {
+    let range = max_value as u128 + 1;
+
+    // 2^256 mod range, the size of the biased tail
+    let tail = (0..32).fold(1u128, |acc, _| (acc * 256) % range);
+    if tail != 0 {
+        // seed >= 2^256 - tail exactly when its complement is below tail
+        let (high, low) = seed.split_at(24);
+        if high.iter().all(|byte| *byte == u8::MAX) {
+            let mut low_bytes = [0u8; 8];
+            low_bytes.copy_from_slice(low);
+            if (!u64::from_be_bytes(low_bytes) as u128) < tail {
+                return None;
+            }
+        }
+    }
+
+    let number = seed
+        .iter()
+        .fold(0u128, |acc, byte| (acc * 256 + *byte as u128) % range);
+    Some(number as u64)
+}
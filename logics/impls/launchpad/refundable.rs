@@ -0,0 +1,223 @@
+use crate::impls::launchpad::launchpad::Internal as LaunchpadInternal;
+use crate::impls::launchpad::types::{Data, Percentage, Shiden34Error, TokenId};
+pub use crate::traits::refundable::Refundable;
+
+use ink::prelude::vec::Vec;
+use openbrush::{
+    contracts::{
+        ownable::*,
+        pausable::*,
+        psp34::extensions::{enumerable::*, metadata::*},
+    },
+    modifiers,
+    traits::{AccountId, Balance, Storage, String},
+};
+
+pub trait Internal {
+    /// Get refund share of the first refund deadline not passed yet
+    fn get_current_refund_share(&self) -> Option<Percentage>;
+
+    /// Check if tokens can still be refunded, the proceeds stay locked until then
+    fn is_refund_window_open(&self) -> bool;
+
+    /// Check deadlines are ascending and shares are percentages that never increase
+    fn check_refund_policy(
+        &self,
+        refund_periods: &Vec<u64>,
+        refund_shares: &Vec<Percentage>,
+    ) -> Result<(), PSP34Error>;
+
+    fn _emit_refund_event(
+        &self,
+        from: AccountId,
+        to: AccountId,
+        id: Option<Id>,
+        price: Balance,
+        refunded: Balance,
+    );
+}
+
+impl<T> Refundable for T
+where
+    T: Storage<Data>
+        + Storage<psp34::Data<enumerable::Balances>>
+        + Storage<ownable::Data>
+        + Storage<pausable::Data>
+        + Storage<metadata::Data>
+        + psp34::extensions::metadata::PSP34Metadata
+        + psp34::Internal
+        + pausable::Internal,
+{
+    default fn refund(&mut self, token_id: TokenId) -> Result<Balance, PSP34Error> {
+        let caller_id = Self::env().caller();
+        let refund_address = self
+            .data::<Data>()
+            .refund_address
+            .ok_or(PSP34Error::Custom(String::from(
+                Shiden34Error::NotRefundable.as_str(),
+            )))?;
+        let owner = self
+            .data::<psp34::Data<enumerable::Balances>>()
+            .owner_of(Id::U64(token_id))
+            .ok_or(PSP34Error::TokenNotExists)?;
+        if owner != caller_id {
+            return Err(PSP34Error::NotApproved);
+        }
+
+        let (buyer, _) =
+            self.data::<Data>()
+                .token_sales
+                .get(token_id)
+                .ok_or(PSP34Error::Custom(String::from(
+                    Shiden34Error::NotRefundable.as_str(),
+                )))?;
+        let price = self.get_refundable_price(token_id)?;
+        let refund_share =
+            self.get_current_refund_share()
+                .ok_or(PSP34Error::Custom(String::from(
+                    Shiden34Error::RefundPeriodOver.as_str(),
+                )))?;
+        let refunded = (price * refund_share).saturating_div(100);
+
+        self._transfer_token(refund_address, Id::U64(token_id), Vec::new())?;
+        self.data::<Data>().token_sales.remove(token_id);
+        self.data::<Data>().total_sales -= refunded;
+        self.data::<Data>().total_refund += refunded;
+
+        // the refunded purchase no longer counts in what its buyer paid
+        let bought = self.data::<Data>().account_bought.get(buyer).unwrap_or(0);
+        self.data::<Data>()
+            .account_bought
+            .insert(buyer, &bought.saturating_sub(1));
+        let paid = self.data::<Data>().account_paid.get(buyer).unwrap_or(0);
+        self.data::<Data>()
+            .account_paid
+            .insert(buyer, &paid.saturating_sub(refunded));
+
+        Self::env()
+            .transfer(caller_id, refunded)
+            .map_err(|_| PSP34Error::Custom(String::from(Shiden34Error::RefundFailed.as_str())))?;
+        self._emit_refund_event(
+            caller_id,
+            refund_address,
+            Some(Id::U64(token_id)),
+            price,
+            refunded,
+        );
+        Ok(refunded)
+    }
+
+    default fn get_refund_amount(&self, token_id: TokenId) -> Balance {
+        match (
+            self.get_refundable_price(token_id),
+            self.get_current_refund_share(),
+        ) {
+            (Ok(price), Some(refund_share)) => (price * refund_share).saturating_div(100),
+            _ => 0,
+        }
+    }
+
+    default fn get_refund_periods(&self) -> Vec<u64> {
+        self.data::<Data>().refund_periods.clone()
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_refund_periods(&mut self, refund_periods: Vec<u64>) -> Result<(), PSP34Error> {
+        let refund_shares = self.data::<Data>().refund_shares.clone();
+        self.check_refund_policy(&refund_periods, &refund_shares)?;
+        if self.has_sale_started() {
+            let current_periods = &self.data::<Data>().refund_periods;
+            if refund_periods
+                .iter()
+                .zip(current_periods.iter())
+                .any(|(period, current_period)| period < current_period)
+            {
+                return Err(PSP34Error::Custom(String::from(
+                    Shiden34Error::SaleAlreadyStarted.as_str(),
+                )));
+            }
+        }
+        self.data::<Data>().refund_periods = refund_periods;
+        Ok(())
+    }
+
+    default fn get_refund_shares(&self) -> Vec<Percentage> {
+        self.data::<Data>().refund_shares.clone()
+    }
+
+    default fn get_refund_address(&self) -> AccountId {
+        self.data::<Data>()
+            .refund_address
+            .unwrap_or(AccountId::from([0; 32]))
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_refund_policy(
+        &mut self,
+        refund_periods: Vec<u64>,
+        refund_shares: Vec<Percentage>,
+        refund_address: AccountId,
+    ) -> Result<(), PSP34Error> {
+        if self.has_sale_started() {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::SaleAlreadyStarted.as_str(),
+            )));
+        }
+        self.check_refund_policy(&refund_periods, &refund_shares)?;
+        self.data::<Data>().refund_periods = refund_periods;
+        self.data::<Data>().refund_shares = refund_shares;
+        self.data::<Data>().refund_address = Some(refund_address);
+        Ok(())
+    }
+
+    default fn get_total_refund(&self) -> Balance {
+        self.data::<Data>().total_refund
+    }
+}
+
+impl<T> Internal for T
+where
+    T: Storage<Data> + Storage<psp34::Data<enumerable::Balances>> + psp34::Internal,
+{
+    default fn get_current_refund_share(&self) -> Option<Percentage> {
+        let current_timestamp = Self::env().block_timestamp();
+        let data = self.data::<Data>();
+        data.refund_periods
+            .iter()
+            .position(|period| current_timestamp <= *period)
+            .map(|index| data.refund_shares[index])
+    }
+
+    default fn is_refund_window_open(&self) -> bool {
+        self.get_current_refund_share().is_some()
+    }
+
+    default fn check_refund_policy(
+        &self,
+        refund_periods: &Vec<u64>,
+        refund_shares: &Vec<Percentage>,
+    ) -> Result<(), PSP34Error> {
+        let ascending = refund_periods.windows(2).all(|pair| pair[0] < pair[1]);
+        let non_increasing = refund_shares.windows(2).all(|pair| pair[0] >= pair[1]);
+        if refund_periods.len() != refund_shares.len()
+            || !ascending
+            || !non_increasing
+            || refund_shares.iter().any(|share| *share > 100)
+        {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::InvalidRefundPolicy.as_str(),
+            )));
+        }
+        Ok(())
+    }
+
+    default fn _emit_refund_event(
+        &self,
+        _from: AccountId,
+        _to: AccountId,
+        _id: Option<Id>,
+        _price: Balance,
+        _refunded: Balance,
+    ) {
+    }
+}
@@ -1,5 +1,6 @@
 use ink::prelude::vec::Vec;
 use ink::storage::Mapping;
+use openbrush::contracts::psp34::Id;
 use openbrush::traits::{Balance, String};
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 
@@ -9,68 +10,289 @@ pub type Percentage = u128;
 pub type TokenId = u64;
 pub type BlockTimestamp = u64;
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
 pub enum MintingStatus {
     Closed,
-    Prepresale,
-    Presale,
-    Public,
+    /// A phase of the sale schedule is running
+    Phase(PhaseId),
     End,
 }
 
 pub type MintingStatusIndex = u8;
 
+pub type PhaseId = u8;
+
+/// Phases seeded by the constructor, kept for the prepresale and presale messages
+pub const PREPRESALE_PHASE: PhaseId = 0;
+pub const PRESALE_PHASE: PhaseId = 1;
+
+/// Phase ids stay below the minting types used by redemptions
+pub const MAX_PHASES: usize = 127;
+
+/// Partner tokens a gated mint can check, each costs a cross-contract call
+pub const MAX_GATED_TOKENS: usize = 20;
+
+/// Time after a raffle phase closes within which it has to be drawn
+pub const DEFAULT_RAFFLE_DRAW_TIMEOUT: MilliSeconds = 7 * 24 * 60 * 60 * 1000;
+
+/// One step of the sale schedule
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Phase {
+    pub name: String,
+    /// The phase runs once the block timestamp is past `start_at`, until the next phase starts
+    pub start_at: u64,
+    /// Close the phase early, leaving the sale closed until the next phase starts
+    pub end_at: Option<u64>,
+    /// Price per token, or the start price of a dutch auction
+    pub price: Balance,
+    pub dutch_auction: Option<DutchAuction>,
+    /// Accounts enter a raffle with a deposit of the price instead of minting,
+    /// winners are drawn once the phase is over
+    pub raffle: bool,
+    /// Only accounts with allowlist slots for this phase can mint, one slot per token
+    pub allowlist_only: bool,
+    pub max_per_account: Option<u64>,
+    /// Tokens allocated to the phase, unsold allocation rolls over to the next capped phase
+    pub max_supply: Option<u64>,
+}
+
+/// Start and end of a phase, as in `Phase`
+pub type PhaseSchedule = (u64, Option<u64>);
+
+pub type RedemptionTierId = u8;
+
+/// Tokens minted through a redemption are recorded in `minting_type_for_token`
+/// as this value plus their tier id
+pub const REDEEMED_MINTING_TYPE: MintingStatusIndex = 128;
+
+/// Burning `burn_amount` tokens of the collection mints one token of the tier
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct RedemptionTier {
+    pub burn_amount: u64,
+    /// Tiers mint ids above `max_supply`, each one in its own contiguous range
+    pub first_token_id: TokenId,
+    pub max_supply: u64,
+    pub minted: u64,
+}
+
+/// How token ids are handed out on mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum IdAssignment {
+    /// Ids are minted in ascending order or chosen, their metadata is shuffled by randomness
+    /// revealed once the sale has ended
+    #[default]
+    Random,
+    /// Ids are minted in ascending order
+    Sequential,
+    /// Ids are minted in ascending order, their metadata is shifted by a starting index drawn
+    /// once the sale has ended
+    Offset,
+}
+
+/// Price of a phase dropping by `price_step` every `step_interval` from its start, down to `floor_price`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct DutchAuction {
+    pub floor_price: Balance,
+    pub price_step: Balance,
+    pub step_interval: u64,
+    /// Buyers can claim back what they paid above the clearing price once the auction ends
+    pub rebate: bool,
+}
+
+/// Partner collection whose holders get allowlist slots for each token they hold
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct TokenGate {
+    /// Address of a PSP34 contract implementing the enumerable extension
+    pub collection: AccountId,
+    pub slots_per_token: u64,
+}
+
+/// English auction of a single unminted token id, bids are escrowed by the contract
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct TokenAuction {
+    pub reserve_price: Balance,
+    pub end_at: u64,
+    pub highest_bidder: Option<AccountId>,
+    pub highest_bid: Balance,
+}
+
+/// Mint authorization signed off-chain by the voucher signer, usable once per nonce
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct MintVoucher {
+    pub account: AccountId,
+    pub phase_id: PhaseId,
+    pub max_amount: u64,
+    /// Price per token, replacing the phase price
+    pub price: Balance,
+    pub expires_at: u64,
+    pub nonce: u64,
+}
+
+/// What allows a paid mint in an allowlist phase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MintAuthorization {
+    /// Allowlist slots stored for the account
+    Allowlist,
+    /// Allocation proven against the Merkle root of the phase
+    MerkleProof(u64),
+    /// Voucher checked by the caller, minting at the voucher price
+    Voucher(Balance),
+    /// Allowlist slots, then the slots of the given partner collection tokens
+    TokenGate(Vec<(AccountId, Id)>),
+}
+
 impl MintingStatus {
-    pub fn to_index(&self) -> u8 {
+    pub fn to_index(&self) -> MintingStatusIndex {
         match self {
             MintingStatus::Closed => return 0,
-            MintingStatus::Prepresale => return 1,
-            MintingStatus::Presale => return 2,
-            MintingStatus::Public => return 3,
-            MintingStatus::End => return 4,
-        }
-    }
-
-    pub fn from(index: u8) -> Self {
-        if index == 0 {
-            return MintingStatus::Closed;
-        } else if index == 1 {
-            return MintingStatus::Prepresale;
-        } else if index == 2 {
-            return MintingStatus::Presale;
-        } else if index == 3 {
-            return MintingStatus::Public;
-        } else {
-            return MintingStatus::End;
+            MintingStatus::Phase(phase_id) => return phase_id + 1,
+            MintingStatus::End => return MintingStatusIndex::MAX,
         }
     }
 }
 
+/// The layout differs from the released contracts under the same key, so deployments of those
+/// must not move to this code with `set_code`
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
     pub collection_id: u32,
     pub max_supply: u64,
-    pub price_per_mint: Balance,
     pub max_amount: u64,
-    pub token_set: Vec<u16>,
-    pub pseudo_random_salt: u64,
-    pub forced_minting_status: Option<u8>,
-    pub public_sale_start_at: u64,
-    pub public_sale_end_at: Option<u64>,
-    pub prepresale_start_at: u64,
-    pub prepresale_price_per_mint: Balance,
-    pub prepresale_whitelisted: Mapping<AccountId, u64>,
-    pub presale_start_at: u64,
-    pub presale_price_per_mint: Balance,
-    pub presale_whitelisted: Mapping<AccountId, u64>,
+    /// Unminted token ids, stored as a sparse array of `token_set_length` entries.
+    /// An index that was never written holds `max_supply - index`.
+    pub token_set: Mapping<u64, TokenId>,
+    /// Reverse lookup of `token_set`, a token id that was never moved sits at `max_supply - id`
+    pub token_set_position: Mapping<TokenId, u64>,
+    pub token_set_length: u64,
+    pub id_assignment: IdAssignment,
+    /// Tokens set aside for `mint_project`, sale mints never take from it
+    pub reserved_supply: u64,
+    pub reserved_minted: u64,
+    /// Count airdrops against the reserved supply instead of the sale supply
+    pub airdrop_from_reserve: bool,
+    pub starting_index: Option<u64>,
+    pub randomness_commitment: Option<[u8; 32]>,
+    /// Commitment taken by the first mint of a shuffled or offset collection, its secret is only
+    /// revealed once the sale ended
+    pub metadata_commitment: Option<[u8; 32]>,
+    /// Hash chain over the mints made before the sale ended
+    pub mint_entropy: [u8; 32],
+    pub metadata_seed: Option<[u8; 32]>,
+    /// Overrides the status derived from the schedule
+    pub forced_minting_status: Option<MintingStatus>,
+    /// Sale phases ordered by start time
+    pub phases: Vec<Phase>,
+    /// Remaining allowlist slots of an account in a phase
+    pub allowlist: Mapping<(PhaseId, AccountId), u64>,
+    /// Accounts of the allowlist of a phase, listed in a swap-and-pop array for paging
+    pub allowlist_accounts: Mapping<(PhaseId, u64), AccountId>,
+    pub allowlist_account_position: Mapping<(PhaseId, AccountId), u64>,
+    pub allowlist_length: Mapping<PhaseId, u64>,
+    /// Partner collections giving slots in an allowlist phase
+    pub token_gates: Mapping<PhaseId, Vec<TokenGate>>,
+    /// Slots used per partner token, so that a token gives its slots once whoever holds it
+    pub gated_token_claimed: Mapping<(PhaseId, AccountId, Id), u64>,
+    /// Merkle root of `(account, allocation)` leaves allowlisting accounts without storing them
+    pub allowlist_roots: Mapping<PhaseId, [u8; 32]>,
+    /// Allocation of a Merkle allowlist consumed by an account
+    pub allowlist_claimed: Mapping<(PhaseId, AccountId), u64>,
+    /// Compressed ECDSA public key signing mint vouchers
+    pub voucher_signer: Option<[u8; 33]>,
+    pub used_voucher_nonces: Mapping<u64, ()>,
+    pub phase_minted: Mapping<PhaseId, u64>,
+    pub phase_account_minted: Mapping<(PhaseId, AccountId), u64>,
+    /// Price of the latest mint in a rebate auction
+    pub auction_clearing_price: Mapping<PhaseId, Balance>,
+    /// Amount paid in a rebate auction and not rebated yet, per phase and per account
+    pub auction_sales: Mapping<PhaseId, Balance>,
+    pub auction_paid: Mapping<(PhaseId, AccountId), Balance>,
+    /// Tokens minted at the auction price, per phase and per account, voucher mints excluded
+    pub auction_minted: Mapping<PhaseId, u64>,
+    pub auction_account_minted: Mapping<(PhaseId, AccountId), u64>,
+    /// Raffle entrants in entry order, one storage cell each
+    pub raffle_entries: Mapping<(PhaseId, u64), AccountId>,
+    pub raffle_entry_count: Mapping<PhaseId, u64>,
+    /// Deposits of raffle entrants, cleared for winners on the draw and for losers on refund
+    pub raffle_deposits: Mapping<(PhaseId, AccountId), Balance>,
+    /// Commitment taken by the first entry of a raffle, its secret is only revealed once the
+    /// phase is over and seeds the draw
+    pub raffle_commitments: Mapping<PhaseId, [u8; 32]>,
+    pub raffle_seeds: Mapping<PhaseId, [u8; 32]>,
+    /// Entry index at each position of the draw shuffle, the position itself until swapped
+    pub raffle_shuffle: Mapping<(PhaseId, u64), u64>,
+    /// Number of winners fixed when the draw starts, and number drawn so far
+    pub raffle_winner_count: Mapping<PhaseId, u64>,
+    pub raffle_drawn_count: Mapping<PhaseId, u64>,
+    /// Tokens kept out of the sale supply for winners not drawn yet
+    pub raffle_undrawn_supply: u64,
+    /// Entrants get their deposit back once a raffle is not drawn this long after it closed
+    pub raffle_draw_timeout: MilliSeconds,
     pub minting_type_for_token: Mapping<TokenId, MintingStatusIndex>,
+    pub redemption_tiers: Vec<RedemptionTier>,
+    /// Token ids taken out of the token set to be auctioned
+    pub token_auctions: Mapping<TokenId, TokenAuction>,
+    /// Bids of outbid bidders, withdrawn by the bidders
+    pub bid_refunds: Mapping<AccountId, Balance>,
     pub total_sales: Balance,
+    /// Sales needed for the proceeds to be withdrawable, below it buyers are refunded
+    pub min_sales: Option<Balance>,
+    /// Amount paid and number of tokens bought per account, refunded when the sale fails
+    pub account_paid: Mapping<AccountId, Balance>,
+    pub account_bought: Mapping<AccountId, u64>,
+    /// Buyer and price paid of a sale token, cleared once the token is refunded
+    pub token_sales: Mapping<TokenId, (AccountId, Balance)>,
+    /// Refund deadlines, a token is refunded the share of its price of the first deadline not passed
+    #[cfg(feature = "refundable")]
+    pub refund_periods: Vec<u64>,
+    #[cfg(feature = "refundable")]
+    pub refund_shares: Vec<Percentage>,
+    /// Account receiving the refunded tokens
+    #[cfg(feature = "refundable")]
+    pub refund_address: Option<AccountId>,
+    #[cfg(feature = "refundable")]
+    pub total_refund: Balance,
+    /// Accept mint payments above the price and refund the excess to the caller
+    pub accept_overpayment: bool,
+    /// Extra price on top of the phase price for minting a chosen token id
+    pub mint_id_premium: Balance,
     pub withdrawn_sales_project: Balance,
     pub withdrawn_sales_launchpad: Balance,
     pub launchpad_fee: Percentage,
     pub project_treasury: Option<AccountId>,
     pub launchpad_treasury: Option<AccountId>,
+    pub placeholder_uri: String,
+    pub provenance_hash: Option<String>,
+    pub revealed: bool,
     pub attribute_count: u32,
     pub attribute_names: Mapping<u32, Vec<u8>>,
 }
@@ -87,6 +309,53 @@ pub enum Shiden34Error {
     UnableToMint,
     RefundFailed,
     Unauthorized,
+    RandomnessCommitmentPending,
+    InvalidRandomnessReveal,
+    RandomnessNotRevealed,
+    RandomnessNotCommitted,
+    RandomnessRevealTooEarly,
+    AlreadyRevealed,
+    NotRevealed,
+    AccountMintLimitExceeded,
+    ReserveExhausted,
+    TokenNotAvailable,
+    UnsupportedIdAssignment,
+    InvalidRedemptionTier,
+    BadRedemptionAmount,
+    RedemptionTierFull,
+    PhaseIsFull,
+    InvalidPhase,
+    InvalidSchedule,
+    SaleAlreadyStarted,
+    PhaseAlreadyRunning,
+    AuctionNotEnded,
+    NoRebate,
+    AuctionNotFound,
+    AuctionEnded,
+    InvalidAuction,
+    BidTooLow,
+    NotRafflePhase,
+    AlreadyEnteredRaffle,
+    RaffleNotDrawn,
+    RaffleAlreadyDrawn,
+    RaffleDrawExpired,
+    NoRaffleRefund,
+    NoBidRefund,
+    SaleFailed,
+    SaleNotFailed,
+    BadRefundAmount,
+    NotRefundable,
+    RefundPeriodOver,
+    InvalidRefundPolicy,
+    InvalidAllowlistProof,
+    InvalidVoucher,
+    VoucherExpired,
+    VoucherAlreadyUsed,
+    NotAllowlisted,
+    InvalidTokenGate,
+    TooManyGatedTokens,
+    GatedTokenNotOwned,
+    BadAllowlistAmount,
 }
 
 impl Shiden34Error {
@@ -100,6 +369,55 @@ impl Shiden34Error {
             Shiden34Error::UnableToMint => String::from("UnableToMint"),
             Shiden34Error::RefundFailed => String::from("RefundFailed"),
             Shiden34Error::Unauthorized => String::from("Unauthorized"),
+            Shiden34Error::RandomnessCommitmentPending => {
+                String::from("RandomnessCommitmentPending")
+            }
+            Shiden34Error::InvalidRandomnessReveal => String::from("InvalidRandomnessReveal"),
+            Shiden34Error::RandomnessNotRevealed => String::from("RandomnessNotRevealed"),
+            Shiden34Error::RandomnessNotCommitted => String::from("RandomnessNotCommitted"),
+            Shiden34Error::RandomnessRevealTooEarly => String::from("RandomnessRevealTooEarly"),
+            Shiden34Error::AlreadyRevealed => String::from("AlreadyRevealed"),
+            Shiden34Error::NotRevealed => String::from("NotRevealed"),
+            Shiden34Error::AccountMintLimitExceeded => String::from("AccountMintLimitExceeded"),
+            Shiden34Error::ReserveExhausted => String::from("ReserveExhausted"),
+            Shiden34Error::TokenNotAvailable => String::from("TokenNotAvailable"),
+            Shiden34Error::UnsupportedIdAssignment => String::from("UnsupportedIdAssignment"),
+            Shiden34Error::InvalidRedemptionTier => String::from("InvalidRedemptionTier"),
+            Shiden34Error::BadRedemptionAmount => String::from("BadRedemptionAmount"),
+            Shiden34Error::RedemptionTierFull => String::from("RedemptionTierFull"),
+            Shiden34Error::PhaseIsFull => String::from("PhaseIsFull"),
+            Shiden34Error::InvalidPhase => String::from("InvalidPhase"),
+            Shiden34Error::InvalidSchedule => String::from("InvalidSchedule"),
+            Shiden34Error::SaleAlreadyStarted => String::from("SaleAlreadyStarted"),
+            Shiden34Error::PhaseAlreadyRunning => String::from("PhaseAlreadyRunning"),
+            Shiden34Error::AuctionNotEnded => String::from("AuctionNotEnded"),
+            Shiden34Error::NoRebate => String::from("NoRebate"),
+            Shiden34Error::AuctionNotFound => String::from("AuctionNotFound"),
+            Shiden34Error::AuctionEnded => String::from("AuctionEnded"),
+            Shiden34Error::InvalidAuction => String::from("InvalidAuction"),
+            Shiden34Error::BidTooLow => String::from("BidTooLow"),
+            Shiden34Error::NotRafflePhase => String::from("NotRafflePhase"),
+            Shiden34Error::AlreadyEnteredRaffle => String::from("AlreadyEnteredRaffle"),
+            Shiden34Error::RaffleNotDrawn => String::from("RaffleNotDrawn"),
+            Shiden34Error::RaffleAlreadyDrawn => String::from("RaffleAlreadyDrawn"),
+            Shiden34Error::RaffleDrawExpired => String::from("RaffleDrawExpired"),
+            Shiden34Error::NoRaffleRefund => String::from("NoRaffleRefund"),
+            Shiden34Error::NoBidRefund => String::from("NoBidRefund"),
+            Shiden34Error::SaleFailed => String::from("SaleFailed"),
+            Shiden34Error::SaleNotFailed => String::from("SaleNotFailed"),
+            Shiden34Error::BadRefundAmount => String::from("BadRefundAmount"),
+            Shiden34Error::NotRefundable => String::from("NotRefundable"),
+            Shiden34Error::RefundPeriodOver => String::from("RefundPeriodOver"),
+            Shiden34Error::InvalidRefundPolicy => String::from("InvalidRefundPolicy"),
+            Shiden34Error::InvalidAllowlistProof => String::from("InvalidAllowlistProof"),
+            Shiden34Error::InvalidVoucher => String::from("InvalidVoucher"),
+            Shiden34Error::VoucherExpired => String::from("VoucherExpired"),
+            Shiden34Error::VoucherAlreadyUsed => String::from("VoucherAlreadyUsed"),
+            Shiden34Error::NotAllowlisted => String::from("NotAllowlisted"),
+            Shiden34Error::InvalidTokenGate => String::from("InvalidTokenGate"),
+            Shiden34Error::TooManyGatedTokens => String::from("TooManyGatedTokens"),
+            Shiden34Error::GatedTokenNotOwned => String::from("GatedTokenNotOwned"),
+            Shiden34Error::BadAllowlistAmount => String::from("BadAllowlistAmount"),
         }
     }
 }
@@ -0,0 +1,10 @@
+use crate::impls::launchpad::randomness::keccak256;
+use crate::impls::launchpad::types::MintVoucher;
+use openbrush::traits::AccountId;
+use scale::Encode;
+
+/// Hash signed by the voucher signer, the keccak256 of the SCALE encoded `(contract, voucher)`.
+/// Binding the contract address keeps a voucher from being replayed on another collection.
+pub fn voucher_message_hash(contract: &AccountId, voucher: &MintVoucher) -> [u8; 32] {
+    keccak256(&(contract, voucher).encode())
+}
@@ -1,6 +1,7 @@
 use ink::prelude::string::{String as PreludeString, ToString};
 
-use crate::impls::launchpad::types::Data;
+use crate::impls::launchpad::launchpad::Internal as LaunchpadInternal;
+use crate::impls::launchpad::types::{Data, Shiden34Error};
 pub use crate::traits::psp34_traits::Psp34Traits;
 
 use ink::prelude::vec::Vec;
@@ -15,6 +16,8 @@ use openbrush::{
 
 pub trait Internal {
     fn token_exists(&self, id: Id) -> Result<(), PSP34Error>;
+
+    fn _emit_revealed_event(&self, base_uri: String);
 }
 
 impl<T> Internal for T
@@ -28,6 +31,8 @@ where
             .ok_or(PSP34Error::TokenNotExists)?;
         Ok(())
     }
+
+    default fn _emit_revealed_event(&self, _base_uri: String) {}
 }
 
 impl<T> Psp34Traits for T
@@ -36,17 +41,30 @@ where
         + Storage<ownable::Data>
         + Storage<metadata::Data>
         + psp34::extensions::metadata::PSP34Metadata
+        + psp34::Internal
         + Storage<Data>,
 {
     /// Get URI from token ID
     default fn token_uri(&self, token_id: u64) -> PreludeString {
+        let metadata_id = match self.get_metadata_id_internal(token_id) {
+            Some(metadata_id) if self.data::<Data>().revealed => metadata_id,
+            _ => {
+                // a placeholder ending with a slash is a path like the baseUri, with a file per token
+                let placeholder = uri_string(self.data::<Data>().placeholder_uri.clone());
+                if !placeholder.ends_with('/') {
+                    return placeholder;
+                }
+                return placeholder + &token_id.to_string() + &PreludeString::from(".json");
+            }
+        };
+
         let value = self.get_attribute(
             self.data::<psp34::Data<enumerable::Balances>>()
                 .collection_id(),
             String::from("baseUri"),
         );
-        let mut token_uri = PreludeString::from_utf8(value.unwrap()).unwrap();
-        token_uri = token_uri + &token_id.to_string() + &PreludeString::from(".json");
+        let mut token_uri = uri_string(value.unwrap_or_default());
+        token_uri = token_uri + &metadata_id.to_string() + &PreludeString::from(".json");
         token_uri
     }
 
@@ -63,6 +81,13 @@ where
             return Err(PSP34Error::Custom(String::from("Unauthorized")));
         }
 
+        // the baseUri is readable through get_attribute, keep it hidden until reveal
+        if !self.data::<Data>().revealed {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::NotRevealed.as_str(),
+            )));
+        }
+
         let id = self
             .data::<psp34::Data<enumerable::Balances>>()
             .collection_id();
@@ -71,6 +96,37 @@ where
         Ok(())
     }
 
+    /// Only Owner can reveal the collection
+    #[modifiers(only_owner)]
+    default fn reveal(&mut self, base_uri: PreludeString) -> Result<(), PSP34Error> {
+        if self.data::<Data>().revealed {
+            return Err(PSP34Error::Custom(String::from(
+                Shiden34Error::AlreadyRevealed.as_str(),
+            )));
+        }
+
+        let id = self
+            .data::<psp34::Data<enumerable::Balances>>()
+            .collection_id();
+        self.data::<metadata::Data>()._set_attribute(
+            id,
+            String::from("baseUri"),
+            base_uri.clone().into_bytes(),
+        );
+        self.data::<Data>().revealed = true;
+
+        self._emit_revealed_event(base_uri.into_bytes());
+        Ok(())
+    }
+
+    default fn is_revealed(&self) -> bool {
+        self.data::<Data>().revealed
+    }
+
+    default fn get_provenance_hash(&self) -> Option<PreludeString> {
+        self.data::<Data>().provenance_hash.clone().map(uri_string)
+    }
+
     /// Only Owner can set multiple attributes to a token
     #[modifiers(only_owner)]
     default fn set_multiple_attributes(
@@ -126,6 +182,11 @@ where
     }
 }
 
+/// URIs are checked to be UTF-8 when they are set, an invalid one reads as empty
+fn uri_string(value: Vec<u8>) -> PreludeString {
+    PreludeString::from_utf8(value).unwrap_or_default()
+}
+
 fn add_attribute_name<T: Storage<Data>>(instance: &mut T, attribute_input: &Vec<u8>) {
     let mut exist: bool = false;
     for index in 0..instance.data::<Data>().attribute_count {
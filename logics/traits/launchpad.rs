@@ -1,10 +1,13 @@
 use ink::prelude::vec::Vec;
 use openbrush::{
-    contracts::psp34::PSP34Error,
-    traits::{AccountId, Balance, String},
+    contracts::psp34::{Id, PSP34Error},
+    traits::{AccountId, Balance},
 };
 
-use crate::impls::launchpad::types::Percentage;
+use crate::impls::launchpad::types::{
+    DutchAuction, IdAssignment, MintVoucher, MintingStatus, Percentage, Phase, PhaseId,
+    RedemptionTier, RedemptionTierId, TokenAuction, TokenGate, TokenId,
+};
 
 #[openbrush::wrapper]
 pub type LaunchpadRef = dyn Launchpad;
@@ -15,14 +18,187 @@ pub trait Launchpad {
     #[ink(message, payable)]
     fn mint(&mut self, to: AccountId, mint_amount: u64) -> Result<(), PSP34Error>;
 
+    /// Mint one or more tokens within an allocation of the Merkle allowlist of the current phase,
+    /// `proof` holds the sibling hashes from the `(caller, allocation)` leaf up to the root
+    #[ink(message, payable)]
+    fn mint_allowlisted(
+        &mut self,
+        to: AccountId,
+        mint_amount: u64,
+        allocation: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Mint one or more tokens for the caller at the voucher price. The signature is the
+    /// recoverable ECDSA signature of the voucher signer over `voucher_message_hash`.
+    #[ink(message, payable)]
+    fn mint_with_voucher(
+        &mut self,
+        voucher: MintVoucher,
+        signature: [u8; 65],
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Mint one or more tokens in an allowlist phase, the slots of the caller's partner
+    /// collection tokens in `gated_tokens`, as `(collection, id)`, make up for missing allowlist
+    /// slots. At most `MAX_GATED_TOKENS` tokens are accepted.
+    #[ink(message, payable)]
+    fn mint_gated(
+        &mut self,
+        to: AccountId,
+        mint_amount: u64,
+        gated_tokens: Vec<(AccountId, Id)>,
+    ) -> Result<(), PSP34Error>;
+
     /// Mint next available token for the caller
     #[ink(message, payable)]
     fn mint_next(&mut self) -> Result<(), PSP34Error>;
 
-    /// Mint for project treasury
+    /// Mint a token id chosen by the caller, at the phase price plus the mint id premium
+    #[ink(message, payable)]
+    fn mint_id(&mut self, to: AccountId, id: TokenId) -> Result<(), PSP34Error>;
+
+    /// Set sales below which the proceeds are refunded to buyers instead of withdrawn,
+    /// only before the sale starts
+    #[ink(message)]
+    fn set_min_sales(&mut self, min_sales: Option<Balance>) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_min_sales(&self) -> Option<Balance>;
+
+    /// Check if the sale ended below the minimum sales, so buyers can claim refunds
+    #[ink(message)]
+    fn is_refundable(&self) -> bool;
+
+    /// Burn tokens the caller bought in the sale to get back the price paid for each of them,
+    /// rebate auction tokens are refunded the clearing price and the rest through the rebate
+    #[ink(message)]
+    fn claim_refund(&mut self, ids: Vec<Id>) -> Result<(), PSP34Error>;
+
+    /// Get amount paid by an account for its mints
+    #[ink(message)]
+    fn get_account_paid_amount(&self, account_id: AccountId) -> Balance;
+
+    /// Enter the running raffle phase, the transferred value must be the phase price
+    #[ink(message, payable)]
+    fn enter_raffle(&mut self) -> Result<(), PSP34Error>;
+
+    /// Draw up to `max_winners` more winners of an ended raffle phase and mint them a token each,
+    /// call again until every winner is drawn. Anyone can draw once the secret bound to the
+    /// raffle is revealed, until the draw timeout.
+    #[ink(message)]
+    fn draw_raffle(&mut self, phase_id: PhaseId, max_winners: u64) -> Result<(), PSP34Error>;
+
+    /// Refund the deposit of a raffle entry which was not drawn, once the draw is complete or
+    /// the draw timeout passed
+    #[ink(message)]
+    fn claim_raffle_refund(&mut self, phase_id: PhaseId) -> Result<(), PSP34Error>;
+
+    /// Get a page of the raffle entrants of a phase in entry order
+    #[ink(message)]
+    fn get_raffle_entries(&self, phase_id: PhaseId, offset: u64, limit: u64) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_raffle_entry_count(&self, phase_id: PhaseId) -> u64;
+
+    /// Get deposit of a raffle entry still held by the contract
+    #[ink(message)]
+    fn get_raffle_deposit(&self, phase_id: PhaseId, account_id: AccountId) -> Balance;
+
+    /// Get seed the raffle winners are drawn with, `None` until the secret bound to the raffle
+    /// is revealed
+    #[ink(message)]
+    fn get_raffle_seed(&self, phase_id: PhaseId) -> Option<[u8; 32]>;
+
+    /// Get commitment taken by the first entry of a raffle phase
+    #[ink(message)]
+    fn get_raffle_commitment(&self, phase_id: PhaseId) -> Option<[u8; 32]>;
+
+    /// Set time after a raffle phase closes within which it has to be fully drawn, before the sale starts
+    #[ink(message)]
+    fn set_raffle_draw_timeout(&mut self, timeout: u64) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_raffle_draw_timeout(&self) -> u64;
+
+    /// Get a page of the raffle winners drawn so far in draw order
+    #[ink(message)]
+    fn get_raffle_winners(&self, phase_id: PhaseId, offset: u64, limit: u64) -> Vec<AccountId>;
+
+    /// Get number of winners of a raffle phase, `None` before the draw starts
+    #[ink(message)]
+    fn get_raffle_winner_count(&self, phase_id: PhaseId) -> Option<u64>;
+
+    /// Take an unminted token id out of the sale to auction it until `end_at`
+    #[ink(message)]
+    fn create_token_auction(
+        &mut self,
+        id: TokenId,
+        reserve_price: Balance,
+        end_at: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Bid the transferred value on an auctioned token, the outbid bid is kept
+    /// for its bidder to withdraw
+    #[ink(message, payable)]
+    fn bid(&mut self, id: TokenId) -> Result<(), PSP34Error>;
+
+    /// Withdraw the bids the caller was outbid with
+    #[ink(message)]
+    fn withdraw_bid_refund(&mut self) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_bid_refund(&self, account_id: AccountId) -> Balance;
+
+    /// Mint an auctioned token to the highest bidder once the auction ended
+    #[ink(message)]
+    fn settle_token_auction(&mut self, id: TokenId) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_token_auction(&self, id: TokenId) -> Option<TokenAuction>;
+
+    /// Set extra price charged by `mint_id`
+    #[ink(message)]
+    fn set_mint_id_premium(&mut self, premium: Balance) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_mint_id_premium(&self) -> Balance;
+
+    /// Check if a token id has not been minted yet
+    #[ink(message)]
+    fn is_available_to_mint(&self, id: TokenId) -> bool;
+
+    /// Mint for project treasury, bounded by the reserved supply
     #[ink(message)]
     fn mint_project(&mut self, to: AccountId, mint_amount: u64) -> Result<(), PSP34Error>;
 
+    /// Mint tokens to many accounts at once, callable by owner or project treasury
+    #[ink(message)]
+    fn airdrop(&mut self, account_id_mint_amounts: Vec<(AccountId, u64)>)
+        -> Result<(), PSP34Error>;
+
+    /// Set whether airdrops are taken from the reserved supply or from the sale supply
+    #[ink(message)]
+    fn set_airdrop_from_reserve(&mut self, airdrop_from_reserve: bool) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_airdrop_from_reserve(&self) -> bool;
+
+    /// Burn a token owned by the caller
+    #[ink(message)]
+    fn burn(&mut self, id: Id) -> Result<(), PSP34Error>;
+
+    /// Add a redemption tier minting one token for every `burn_amount` burned tokens
+    #[ink(message)]
+    fn add_redemption_tier(&mut self, burn_amount: u64, max_supply: u64) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_redemption_tiers(&self) -> Vec<RedemptionTier>;
+
+    /// Burn tokens owned by the caller to mint a token of the redemption tier
+    #[ink(message)]
+    fn redeem(&mut self, tier: RedemptionTierId, ids: Vec<Id>) -> Result<(), PSP34Error>;
+
     #[ink(message)]
     fn get_available_to_withdraw_launchpad(&self) -> Balance;
 
@@ -41,15 +217,44 @@ pub trait Launchpad {
     #[ink(message)]
     fn set_max_mint_amount(&mut self, max_amount: u64) -> Result<(), PSP34Error>;
 
+    /// Set whether mint payments above the price are accepted, the excess is refunded to the caller
+    #[ink(message)]
+    fn set_accept_overpayment(&mut self, accept_overpayment: bool) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_accept_overpayment(&self) -> bool;
+
     /// Get max supply of tokens
     #[ink(message)]
     fn max_supply(&self) -> u64;
 
-    /// Get token price
+    /// Get number of reserved tokens which could still be minted by the project treasury
+    #[ink(message)]
+    fn get_reserve_remaining(&self) -> u64;
+
+    /// Get number of tokens which could still be minted in the sale
+    #[ink(message)]
+    fn get_sale_supply_remaining(&self) -> u64;
+
+    /// Get how token ids are assigned on mint
+    #[ink(message)]
+    fn get_id_assignment(&self) -> IdAssignment;
+
+    /// Get the starting index of the offset id assignment, drawn when the randomness bound to
+    /// the metadata is revealed after the sale has ended
+    #[ink(message)]
+    fn get_starting_index(&self) -> Option<u64>;
+
+    /// Get the id of the metadata served for a token, `None` until the randomness bound to the
+    /// metadata is revealed
+    #[ink(message)]
+    fn get_metadata_id(&self, token_id: TokenId) -> Option<u64>;
+
+    /// Get token price of the last phase
     #[ink(message)]
     fn price(&self) -> Balance;
 
-    /// Get token price presale
+    /// Get token price prepresale
     #[ink(message)]
     fn prepresale_price(&self) -> Balance;
 
@@ -69,6 +274,71 @@ pub trait Launchpad {
     #[ink(message)]
     fn get_public_sale_end_at(&self) -> Option<u64>;
 
+    /// Move the start of the prepresale phase, keeping prepresale <= presale <= public < end
+    #[ink(message)]
+    fn set_prepresale_start_at(&mut self, start_at: u64) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn set_presale_start_at(&mut self, start_at: u64) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn set_public_sale_start_at(&mut self, start_at: u64) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn set_public_sale_end_at(&mut self, end_at: Option<u64>) -> Result<(), PSP34Error>;
+
+    /// Replace the sale schedule, phases which have started are kept as they are
+    /// except for the end of the running phase
+    #[ink(message)]
+    fn set_phases(&mut self, phases: Vec<Phase>) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_phases(&self) -> Vec<Phase>;
+
+    /// Get the running phase, `None` while the sale is closed or ended
+    #[ink(message)]
+    fn get_current_phase(&self) -> Option<PhaseId>;
+
+    /// Set max number of tokens minted during a phase, `None` for no cap.
+    /// Unsold allocation of a phase rolls over to the next capped phase.
+    #[ink(message)]
+    fn set_phase_allocation(
+        &mut self,
+        phase_id: PhaseId,
+        max_supply: Option<u64>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Get number of tokens left to mint in a phase, `None` when the phase is not capped
+    #[ink(message)]
+    fn get_phase_remaining_allocation(&self, phase_id: PhaseId) -> Option<u64>;
+
+    /// Get token price of a phase at the current block timestamp
+    #[ink(message)]
+    fn get_phase_price(&self, phase_id: PhaseId) -> Result<Balance, PSP34Error>;
+
+    /// Turn a phase which is not running into a dutch auction, or back to a fixed price with `None`
+    #[ink(message)]
+    fn set_phase_dutch_auction(
+        &mut self,
+        phase_id: PhaseId,
+        dutch_auction: Option<DutchAuction>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Claim back what the caller paid above the clearing price of an ended rebate auction
+    #[ink(message)]
+    fn claim_rebate(&mut self, phase_id: PhaseId) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_rebate_amount(&self, phase_id: PhaseId, account_id: AccountId) -> Balance;
+
+    /// Get number of tokens minted during a phase
+    #[ink(message)]
+    fn get_phase_minted_amount(&self, phase_id: PhaseId) -> u64;
+
+    /// Get number of tokens an account has minted during a phase
+    #[ink(message)]
+    fn get_account_phase_minted_amount(&self, phase_id: PhaseId, account_id: AccountId) -> u64;
+
     #[ink(message)]
     fn get_launchpad_fee(&self) -> Percentage;
 
@@ -82,6 +352,128 @@ pub trait Launchpad {
     #[ink(message)]
     fn get_max_mint_amount(&mut self) -> u64;
 
+    /// Add tokens an account could mint during an allowlist phase to its remaining slots
+    #[ink(message)]
+    fn add_account_to_allowlist(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn add_account_to_allowlist_batch(
+        &mut self,
+        phase_id: PhaseId,
+        account_id_mint_amounts: Vec<(AccountId, u64)>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Overwrite the remaining slots of an account in an allowlist phase
+    #[ink(message)]
+    fn set_account_allowlist_minting_amount(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Add slots to the remaining slots of an account in an allowlist phase
+    #[ink(message)]
+    fn increase_account_allowlist_minting_amount(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Take slots off the remaining slots of an account in an allowlist phase,
+    /// fails if the account has fewer slots left
+    #[ink(message)]
+    fn decrease_account_allowlist_minting_amount(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+        mint_amount: u64,
+    ) -> Result<(), PSP34Error>;
+
+    /// Take an account and its remaining slots off the allowlist of a phase
+    #[ink(message)]
+    fn remove_account_from_allowlist(
+        &mut self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn remove_account_from_allowlist_batch(
+        &mut self,
+        phase_id: PhaseId,
+        account_ids: Vec<AccountId>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Get a page of the allowlist of a phase with the remaining slots of each account,
+    /// removing an account moves the last account into its position
+    #[ink(message)]
+    fn get_allowlist(&self, phase_id: PhaseId, offset: u64, limit: u64) -> Vec<(AccountId, u64)>;
+
+    /// Get number of accounts on the allowlist of a phase
+    #[ink(message)]
+    fn get_allowlist_count(&self, phase_id: PhaseId) -> u64;
+
+    /// Set partner collections of an allowlist phase, each held token gives `slots_per_token`
+    /// slots to accounts without enough allowlist slots. An empty list removes the gating.
+    #[ink(message)]
+    fn set_token_gates(
+        &mut self,
+        phase_id: PhaseId,
+        token_gates: Vec<TokenGate>,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_token_gates(&self, phase_id: PhaseId) -> Vec<TokenGate>;
+
+    /// Get slots of a partner token already used in a phase
+    #[ink(message)]
+    fn get_gated_token_claimed_amount(
+        &self,
+        phase_id: PhaseId,
+        collection: AccountId,
+        id: Id,
+    ) -> u64;
+
+    #[ink(message)]
+    fn get_account_allowlist_minting_amount(
+        &self,
+        phase_id: PhaseId,
+        account_id: AccountId,
+    ) -> Option<u64>;
+
+    /// Set or rotate the compressed ECDSA public key signing mint vouchers, `None` disables vouchers
+    #[ink(message)]
+    fn set_voucher_signer(&mut self, voucher_signer: Option<[u8; 33]>) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_voucher_signer(&self) -> Option<[u8; 33]>;
+
+    #[ink(message)]
+    fn is_voucher_nonce_used(&self, nonce: u64) -> bool;
+
+    /// Set Merkle root of the `(account, allocation)` allowlist of a phase, `None` removes it
+    #[ink(message)]
+    fn set_allowlist_root(
+        &mut self,
+        phase_id: PhaseId,
+        root: Option<[u8; 32]>,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_allowlist_root(&self, phase_id: PhaseId) -> Option<[u8; 32]>;
+
+    /// Get allocation of the Merkle allowlist of a phase consumed by an account
+    #[ink(message)]
+    fn get_account_allowlist_claimed_amount(&self, phase_id: PhaseId, account_id: AccountId)
+        -> u64;
+
     #[ink(message)]
     fn add_account_to_prepresale(
         &mut self,
@@ -114,9 +506,58 @@ pub trait Launchpad {
     #[ink(message)]
     fn get_account_presale_minting_amount(&self, account_id: AccountId) -> Option<u64>;
 
+    /// Set max number of tokens an account could mint during the last phase, `None` for no limit
+    #[ink(message)]
+    fn set_max_public_mint_per_account(
+        &mut self,
+        max_amount: Option<u64>,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_max_public_mint_per_account(&self) -> Option<u64>;
+
+    /// Get number of tokens an account has minted during the last phase
+    #[ink(message)]
+    fn get_account_public_minted_amount(&self, account_id: AccountId) -> u64;
+
+    /// Pause mints, burns, withdrawals and token transfers, refunds stay claimable
+    #[ink(message)]
+    fn pause(&mut self) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn unpause(&mut self) -> Result<(), PSP34Error>;
+
+    /// Force a minting status over the schedule, `None` to follow the schedule again
+    #[ink(message)]
+    fn set_minting_status(
+        &mut self,
+        minting_status: Option<MintingStatus>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Get the forced minting status, `None` while the status is derived from the schedule
+    #[ink(message)]
+    fn get_forced_minting_status(&self) -> Option<MintingStatus>;
+
+    /// Commit to the keccak256 hash of a secret. The first mint of a random or offset collection
+    /// takes the pending commitment for its metadata assignment, and the first entry of a raffle
+    /// for its draw, both are refused without one.
+    #[ink(message)]
+    fn commit_randomness(&mut self, commitment: [u8; 32]) -> Result<(), PSP34Error>;
+
+    /// Reveal the secret behind a commitment. The one bound to the metadata assignment can only
+    /// be revealed once the sale has ended, it is mixed with every mint made until then, and the
+    /// one bound to a raffle once the raffle phase is over.
+    #[ink(message)]
+    fn reveal_randomness(&mut self, secret: Vec<u8>) -> Result<(), PSP34Error>;
+
+    /// Get the commitment not taken by any draw yet
+    #[ink(message)]
+    fn get_randomness_commitment(&self) -> Option<[u8; 32]>;
+
+    /// Get the commitment bound to the metadata assignment
     #[ink(message)]
-    fn set_minting_status(&mut self, minting_status_index: Option<u8>) -> Result<(), PSP34Error>;
+    fn get_metadata_commitment(&self) -> Option<[u8; 32]>;
 
     #[ink(message)]
-    fn get_minting_status(&self) -> String;
+    fn get_minting_status(&self) -> MintingStatus;
 }
@@ -1,2 +1,4 @@
 pub mod launchpad;
-pub mod psp34_traits;
\ No newline at end of file
+pub mod psp34_traits;
+#[cfg(feature = "refundable")]
+pub mod refundable;
\ No newline at end of file
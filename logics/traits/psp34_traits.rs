@@ -8,7 +8,8 @@ pub type Psp34TraitsRef = dyn Psp34Traits;
 
 #[openbrush::trait_definition]
 pub trait Psp34Traits {
-    /// Set new value for the baseUri
+    /// Set new value for the baseUri, fails with `NotRevealed` before the collection is revealed
+    /// since the baseUri is readable through `get_attribute`
     #[ink(message)]
     fn set_base_uri(&mut self, uri: PreludeString) -> Result<(), PSP34Error>;
 
@@ -31,7 +32,19 @@ pub trait Psp34Traits {
     #[ink(message)]
     fn get_attribute_name(&self, index: u32) -> PreludeString;
 
-    /// Get URI from token ID
+    /// Get URI from token ID, the placeholder URI is returned until the collection is revealed.
+    /// A placeholder URI ending with `/` gets the token ID appended like the baseUri
     #[ink(message)]
     fn token_uri(&self, token_id: u64) -> PreludeString;
+
+    /// Set the baseUri and reveal the collection, can only be called once
+    #[ink(message)]
+    fn reveal(&mut self, base_uri: PreludeString) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn is_revealed(&self) -> bool;
+
+    /// Get the provenance hash committed at deployment
+    #[ink(message)]
+    fn get_provenance_hash(&self) -> Option<PreludeString>;
 }
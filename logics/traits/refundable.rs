@@ -0,0 +1,53 @@
+use ink::prelude::vec::Vec;
+use openbrush::{
+    contracts::psp34::PSP34Error,
+    traits::{AccountId, Balance},
+};
+
+use crate::impls::launchpad::types::{Percentage, TokenId};
+
+#[openbrush::wrapper]
+pub type RefundableRef = dyn Refundable;
+
+/// Refunds of sale tokens during the refund periods.
+/// The messages keep the selectors and types of the released refundable contract, which
+/// `scripts/check-refundable-abi.js` checks against its metadata.
+#[openbrush::trait_definition]
+pub trait Refundable {
+    /// Hand a token over to the refund address for the refund share of its price
+    #[ink(message, selector = 0x91ce70d0)]
+    fn refund(&mut self, token_id: TokenId) -> Result<Balance, PSP34Error>;
+
+    /// Get amount refunded for a token right now
+    #[ink(message, selector = 0x2fc61cfa)]
+    fn get_refund_amount(&self, token_id: TokenId) -> Balance;
+
+    /// Get refund deadlines in ascending order
+    #[ink(message, selector = 0x232757e6)]
+    fn get_refund_periods(&self) -> Vec<u64>;
+
+    /// Move the refund deadlines, once the sale started they can only be extended
+    #[ink(message, selector = 0xe3b959ae)]
+    fn set_refund_periods(&mut self, refund_periods: Vec<u64>) -> Result<(), PSP34Error>;
+
+    /// Get percentage of the price refunded before each refund deadline
+    #[ink(message, selector = 0xe605cdf8)]
+    fn get_refund_shares(&self) -> Vec<Percentage>;
+
+    /// Get account refunded tokens are sent to
+    #[ink(message, selector = 0xc1d0c169)]
+    fn get_refund_address(&self) -> AccountId;
+
+    /// Set refund deadlines, shares and address, only before the sale starts
+    #[ink(message)]
+    fn set_refund_policy(
+        &mut self,
+        refund_periods: Vec<u64>,
+        refund_shares: Vec<Percentage>,
+        refund_address: AccountId,
+    ) -> Result<(), PSP34Error>;
+
+    /// Get sum of all refunds paid out
+    #[ink(message)]
+    fn get_total_refund(&self) -> Balance;
+}
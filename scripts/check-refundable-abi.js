@@ -0,0 +1,124 @@
+// Checks the metadata of a `refundable` build against the released paras_refundable contract:
+// the refund messages keep their selectors, arguments and return types, and the constructor
+// takes the refund arguments at the same place.
+//
+//   cargo contract build --manifest-path contracts/paras_launchpad/Cargo.toml --features refundable
+//   yarn check:refundable-abi [path to the generated metadata]
+
+const fs = require("fs");
+const path = require("path");
+
+const RELEASE = path.join(__dirname, "..", "release", "paras_refundable.json");
+const GENERATED =
+  process.argv[2] ||
+  path.join(
+    __dirname,
+    "..",
+    "contracts",
+    "paras_launchpad",
+    "target",
+    "ink",
+    "paras_launchpad.json"
+  );
+const REFUND_ARGS = ["refund_periods", "refund_shares", "refund_address"];
+
+function readMetadata(file) {
+  return JSON.parse(fs.readFileSync(file, "utf8"));
+}
+
+// Type ids differ between builds, compare the shape of the types they point to instead
+function describeType(metadata, id, seen = []) {
+  if (seen.includes(id)) {
+    return "<recursive>";
+  }
+  const entry = metadata.types.find((type) => type.id === id);
+  const def = entry.type.def;
+  const inner = (typeId) => describeType(metadata, typeId, [...seen, id]);
+  const fields = (list = []) =>
+    list.map((field) => `${field.name || ""}:${inner(field.type)}`).join(",");
+
+  if (def.primitive) return def.primitive;
+  if (def.compact) return `Compact<${inner(def.compact.type)}>`;
+  if (def.sequence) return `Vec<${inner(def.sequence.type)}>`;
+  if (def.array) return `[${inner(def.array.type)};${def.array.len}]`;
+  if (def.tuple) return `(${def.tuple.map(inner).join(",")})`;
+  if (def.composite) return `{${fields(def.composite.fields)}}`;
+  if (def.variant) {
+    const variants = (def.variant.variants || [])
+      .map((variant) => `${variant.name}(${fields(variant.fields)})`)
+      .join("|");
+    return `enum{${variants}}`;
+  }
+  throw new Error(`unknown type definition ${JSON.stringify(def)}`);
+}
+
+function describeArgs(metadata, args) {
+  return args.map((arg) => `${arg.label}:${describeType(metadata, arg.type.type)}`);
+}
+
+function main() {
+  const release = readMetadata(RELEASE);
+  const generated = readMetadata(GENERATED);
+  const errors = [];
+
+  const refundMessages = release.spec.messages.filter((message) =>
+    message.label.toLowerCase().includes("refund")
+  );
+  for (const expected of refundMessages) {
+    const actual = generated.spec.messages.find(
+      (message) => message.selector === expected.selector
+    );
+    if (!actual) {
+      errors.push(`${expected.label}: no message with selector ${expected.selector}`);
+      continue;
+    }
+    for (const key of ["mutates", "payable"]) {
+      if (actual[key] !== expected[key]) {
+        errors.push(`${expected.label}: ${key} is ${actual[key]}, expected ${expected[key]}`);
+      }
+    }
+    const expectedArgs = describeArgs(release, expected.args).join(", ");
+    const actualArgs = describeArgs(generated, actual.args).join(", ");
+    if (actualArgs !== expectedArgs) {
+      errors.push(`${expected.label}: arguments (${actualArgs}), expected (${expectedArgs})`);
+    }
+    const expectedReturn = describeType(release, expected.returnType.type);
+    const actualReturn = describeType(generated, actual.returnType.type);
+    if (actualReturn !== expectedReturn) {
+      errors.push(`${expected.label}: returns ${actualReturn}, expected ${expectedReturn}`);
+    }
+  }
+
+  const expectedConstructor = release.spec.constructors.find((c) => c.label === "new");
+  const actualConstructor = generated.spec.constructors.find((c) => c.label === "new");
+  if (!actualConstructor) {
+    errors.push("new: constructor missing");
+  } else {
+    if (actualConstructor.selector !== expectedConstructor.selector) {
+      errors.push(
+        `new: selector ${actualConstructor.selector}, expected ${expectedConstructor.selector}`
+      );
+    }
+    // the refund arguments follow the end of the public sale in both constructors
+    const refundArgs = (metadata, constructor) => {
+      const labels = constructor.args.map((arg) => arg.label);
+      const start = labels.indexOf("public_sale_end_at") + 1;
+      return describeArgs(metadata, constructor.args.slice(start, start + REFUND_ARGS.length));
+    };
+    const expectedArgs = refundArgs(release, expectedConstructor).join(", ");
+    const actualArgs = refundArgs(generated, actualConstructor).join(", ");
+    if (actualArgs !== expectedArgs) {
+      errors.push(`new: refund arguments (${actualArgs}), expected (${expectedArgs})`);
+    }
+  }
+
+  if (errors.length > 0) {
+    errors.forEach((error) => console.error(error));
+    process.exit(1);
+  }
+  console.log(
+    `${refundMessages.length} refund messages and the constructor match ${path.basename(RELEASE)}`
+  );
+}
+
+main();
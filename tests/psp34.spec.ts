@@ -1,6 +1,7 @@
 import { expect, use } from "chai";
 import chaiAsPromised from "chai-as-promised";
 import { encodeAddress } from "@polkadot/keyring";
+import { keccakAsU8a } from "@polkadot/util-crypto";
 import BN from "bn.js";
 import ParasLaunchpad_factory from "../types/constructors/paras_launchpad";
 import ParasLaunchpad from "../types/contracts/paras_launchpad";
@@ -8,7 +9,12 @@ import ParasLaunchpad from "../types/contracts/paras_launchpad";
 import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
 import { KeyringPair } from "@polkadot/keyring/types";
 import { ReturnNumber } from "@727-ventures/typechain-types";
-import { Id, IdBuilder } from "../types/types-arguments/paras_launchpad";
+import {
+  Id,
+  IdAssignment,
+  IdBuilder,
+  MintingStatusBuilder,
+} from "../types/types-arguments/paras_launchpad";
 
 use(chaiAsPromised);
 
@@ -19,6 +25,7 @@ const TOKEN_URI_1 = "ipfs://tokenUriPrefix/1.json";
 const TOKEN_URI_5 = "ipfs://tokenUriPrefix/5.json";
 const ONE = new BN(1).pow(new BN(1));
 const PRICE_PER_MINT = ONE;
+const RANDOMNESS_SECRET = "launch secret";
 
 // Create a new instance of contract
 const wsProvider = new WsProvider("ws://127.0.0.1:9944");
@@ -51,7 +58,11 @@ describe("Minting psp34 tokens", () => {
           ["ParasLaunchpad"], // name: String,
           ["PR"], // symbol: String,
           [BASE_URI], // base_uri: String,
+          null, // placeholder_uri: Option<String>,
+          null, // provenance_hash: Option<String>,
           MAX_SUPPLY, // max_supply: u64,
+          IdAssignment.random, // id_assignment: IdAssignment,
+          0, // reserved_supply: u64,
           PRICE_PER_MINT, // prepresale_price_per_mint: Balance,
           PRICE_PER_MINT, // presale_price_per_mint: Balance,
           PRICE_PER_MINT, // price_per_mint: Balance,
@@ -67,6 +78,11 @@ describe("Minting psp34 tokens", () => {
       deployer,
       api
     );
+
+    // the first mint binds the commitment to the metadata shuffle, revealed after the sale
+    await contract
+      .withSigner(deployer)
+      .tx.commitRandomness(Array.from(keccakAsU8a(RANDOMNESS_SECRET)));
   }
 
   it("Create collection works", async () => {
@@ -141,10 +157,12 @@ describe("Minting psp34 tokens", () => {
   it("Mint 5 tokens works", async () => {
     await setup();
 
-    await contract.withSigner(deployer).tx.setMintingStatus(3);
+    await contract
+      .withSigner(deployer)
+      .tx.setMintingStatus(MintingStatusBuilder.Phase(2));
 
-    expect((await contract.query.getMintingStatus()).value.ok).to.equal(
-      "0x7075626c6963"
+    expect((await contract.query.getMintingStatus()).value.ok).to.deep.equal(
+      MintingStatusBuilder.Phase(2)
     ); // public
     expect(
       (await contract.query.totalSupply()).value.unwrap().toNumber()
@@ -0,0 +1,174 @@
+import { expect, use } from "chai";
+import chaiAsPromised from "chai-as-promised";
+import BN from "bn.js";
+import ParasLaunchpad_factory from "../types/constructors/paras_launchpad";
+import ParasLaunchpad from "../types/contracts/paras_launchpad";
+
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import { KeyringPair } from "@polkadot/keyring/types";
+import {
+  Id,
+  IdAssignment,
+  IdBuilder,
+  MintingStatusBuilder,
+} from "../types/types-arguments/paras_launchpad";
+
+use(chaiAsPromised);
+
+const MAX_SUPPLY = 100;
+const BASE_URI = "ipfs://tokenUriPrefix/";
+const ONE = new BN(1).pow(new BN(1));
+const PRICE_PER_MINT = ONE;
+const SLOTS_PER_TOKEN = 2;
+
+// Create a new instance of contract
+const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+// Create a keyring instance
+const keyring = new Keyring({ type: "sr25519" });
+
+describe("Minting with partner collection tokens", () => {
+  let api: ApiPromise;
+  let deployer: KeyringPair;
+  let bob: KeyringPair;
+  let charlie: KeyringPair;
+  let projectAccount: KeyringPair;
+  let partner: ParasLaunchpad;
+  let contract: ParasLaunchpad;
+  let partnerTokenId: Id;
+
+  async function deploy(name: string): Promise<ParasLaunchpad> {
+    const factory = new ParasLaunchpad_factory(api, deployer);
+    return new ParasLaunchpad(
+      (
+        await factory.new(
+          [name], // name: String,
+          ["PR"], // symbol: String,
+          [BASE_URI], // base_uri: String,
+          null, // placeholder_uri: Option<String>,
+          null, // provenance_hash: Option<String>,
+          MAX_SUPPLY, // max_supply: u64,
+          IdAssignment.sequential, // id_assignment: IdAssignment,
+          0, // reserved_supply: u64,
+          PRICE_PER_MINT, // prepresale_price_per_mint: Balance,
+          PRICE_PER_MINT, // presale_price_per_mint: Balance,
+          PRICE_PER_MINT, // price_per_mint: Balance,
+          0, // prepresale_start_at: u64,
+          0, // presale_start_at: u64,
+          0, // public_sale_start_at: u64,
+          1711626898000, // public_sale_end_at: u64,
+          10, // launchpad_fee: Percentage,
+          projectAccount.address, // project_treasury: AccountId,
+          deployer.address // launchpad_treasury: AccountId,
+        )
+      ).address,
+      deployer,
+      api
+    );
+  }
+
+  async function setup(): Promise<void> {
+    api = await ApiPromise.create({ provider: wsProvider });
+    deployer = keyring.addFromUri("//Alice");
+    bob = keyring.addFromUri("//Bob");
+    charlie = keyring.addFromUri("//Charlie");
+    projectAccount = keyring.addFromUri("//Dave");
+
+    // Bob buys a token of the partner collection in its public sale
+    partner = await deploy("PartnerCollection");
+    await partner.withSigner(bob).tx.mintNext({ value: PRICE_PER_MINT });
+    partnerTokenId = IdBuilder.U64(
+      (await partner.query.tokenByIndex(0)).value.unwrap().ok.u64
+    );
+
+    // the presale of the launch is open to holders of the partner collection
+    contract = await deploy("ParasLaunchpad");
+    await contract.withSigner(deployer).tx.setTokenGates(1, [
+      { collection: partner.address, slotsPerToken: SLOTS_PER_TOKEN },
+    ]);
+    await contract
+      .withSigner(deployer)
+      .tx.setMintingStatus(MintingStatusBuilder.Phase(1));
+  }
+
+  it("Holder of a partner token mints its slots", async () => {
+    await setup();
+
+    expect((await partner.query.ownerOf(partnerTokenId)).value.ok).to.equal(
+      bob.address
+    );
+
+    await contract
+      .withSigner(bob)
+      .tx.mintGated(
+        bob.address,
+        SLOTS_PER_TOKEN,
+        [[partner.address, partnerTokenId]],
+        { value: PRICE_PER_MINT.muln(SLOTS_PER_TOKEN) }
+      );
+
+    expect((await contract.query.balanceOf(bob.address)).value.ok).to.equal(
+      SLOTS_PER_TOKEN
+    );
+    expect(
+      (
+        await contract.query.getGatedTokenClaimedAmount(
+          1,
+          partner.address,
+          partnerTokenId
+        )
+      ).value.ok
+    ).to.equal(SLOTS_PER_TOKEN);
+
+    // the slots of a token are used once
+    const mintResult = await contract
+      .withSigner(bob)
+      .query.mintGated(bob.address, 1, [[partner.address, partnerTokenId]], {
+        value: PRICE_PER_MINT,
+      });
+    expect(hex2a(mintResult.value.unwrap().err.custom)).to.be.equal(
+      "UnableToMint"
+    );
+  });
+
+  it("Minting with a partner token of someone else fails", async () => {
+    await setup();
+
+    const notOwned = await contract
+      .withSigner(charlie)
+      .query.mintGated(charlie.address, 1, [[partner.address, partnerTokenId]], {
+        value: PRICE_PER_MINT,
+      });
+    expect(hex2a(notOwned.value.unwrap().err.custom)).to.be.equal(
+      "GatedTokenNotOwned"
+    );
+
+    const notMinted = await contract
+      .withSigner(bob)
+      .query.mintGated(
+        bob.address,
+        1,
+        [[partner.address, IdBuilder.U64(MAX_SUPPLY)]],
+        { value: PRICE_PER_MINT }
+      );
+    expect(hex2a(notMinted.value.unwrap().err.custom)).to.be.equal(
+      "GatedTokenNotOwned"
+    );
+
+    // tokens are only used when they are listed
+    const unlisted = await contract
+      .withSigner(bob)
+      .query.mint(bob.address, 1, { value: PRICE_PER_MINT });
+    expect(hex2a(unlisted.value.unwrap().err.custom)).to.be.equal(
+      "UnableToMint"
+    );
+  });
+});
+
+// Helper function to convert error code to string
+function hex2a(psp34CustomError: any): string {
+  var hex = psp34CustomError.toString(); //force conversion
+  var str = "";
+  for (var i = 0; i < hex.length; i += 2)
+    str += String.fromCharCode(parseInt(hex.substr(i, 2), 16));
+  return str.substring(1);
+}